@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+// plain-text file with the name of every unlocked achievement, one per line
+const ACHIEVEMENTS_FILE: &str = "achievements.txt";
+
+// points for Survivor
+const SURVIVOR_SCORE: i32 = 25;
+
+// obstacles in a row without a near miss for Untouchable
+const UNTOUCHABLE_PASSES: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Achievement {
+    FirstFlight,
+    Survivor,
+    Untouchable,
+}
+
+impl Achievement {
+    // every achievement, in the order the achievements screen lists them
+    pub const ALL: [Achievement; 3] = [
+        Achievement::FirstFlight,
+        Achievement::Survivor,
+        Achievement::Untouchable,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstFlight => "First Flight",
+            Achievement::Survivor => "Survivor",
+            Achievement::Untouchable => "Untouchable",
+        }
+    }
+
+    // how to unlock it
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstFlight => "Score a point",
+            Achievement::Survivor => "Score 25 points in one run",
+            Achievement::Untouchable => "Pass 10 obstacles in a row without a near miss",
+        }
+    }
+
+    // the names written to the achievements file, the same as the Debug names
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "FirstFlight" => Some(Achievement::FirstFlight),
+            "Survivor" => Some(Achievement::Survivor),
+            "Untouchable" => Some(Achievement::Untouchable),
+            _ => None,
+        }
+    }
+}
+
+// what has been unlocked, and the progress of the current run towards the rest
+pub struct Achievements {
+    // saved between sessions, in the order they were unlocked
+    unlocked: Vec<Achievement>,
+    // obstacles passed this run since the last near miss
+    clean_passes: i32,
+}
+
+impl Achievements {
+    // read the unlocked achievements
+    // unknown names are skipped and a missing file means nothing is unlocked
+    pub fn load() -> Self {
+        let text = std::fs::read_to_string(ACHIEVEMENTS_FILE).unwrap_or_default();
+        Achievements {
+            unlocked: text
+                .lines()
+                .filter_map(|line| Achievement::from_name(line.trim()))
+                .collect(),
+            clean_passes: 0,
+        }
+    }
+
+    // everything already unlocked, for runs that aren't really played,
+    // nothing is left to unlock so nothing is ever saved
+    pub fn all_unlocked() -> Self {
+        Achievements {
+            unlocked: Achievement::ALL.to_vec(),
+            clean_passes: 0,
+        }
+    }
+
+    fn save(&self) {
+        let mut text = String::new();
+        for achievement in &self.unlocked {
+            // writing to a String can't fail
+            let _ = writeln!(text, "{achievement:?}");
+        }
+        if let Err(err) = std::fs::write(ACHIEVEMENTS_FILE, text) {
+            eprintln!("Could not save achievements to {ACHIEVEMENTS_FILE}: {err}");
+        }
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    // forget the progress of the last run
+    pub fn start_run(&mut self) {
+        self.clean_passes = 0;
+    }
+
+    // an obstacle was passed, a near miss starts the count over
+    pub fn passed(&mut self, near_miss: bool) {
+        self.clean_passes = if near_miss { 0 } else { self.clean_passes + 1 };
+    }
+
+    // unlock whatever the run has earned so far, saving straight away
+    // returns the first newly unlocked achievement, the rest follow on later calls
+    pub fn check(&mut self, score: i32) -> Option<Achievement> {
+        let earned = Achievement::ALL.iter().copied().find(|&achievement| {
+            !self.is_unlocked(achievement)
+                && match achievement {
+                    Achievement::FirstFlight => score >= 1,
+                    Achievement::Survivor => score >= SURVIVOR_SCORE,
+                    Achievement::Untouchable => self.clean_passes >= UNTOUCHABLE_PASSES,
+                }
+        })?;
+        self.unlocked.push(earned);
+        self.save();
+        Some(earned)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::config::Screen;
+
+// smallest playfield the game still works on
+pub const MIN_WIDTH: i32 = 30;
+pub const MIN_HEIGHT: i32 = 20;
+
+// command-line options
+pub struct Args {
+    // playfield size, --width and --height, the saved size otherwise
+    pub screen: Screen,
+    // replay the same run every time, --seed
+    pub seed: Option<u64>,
+    // play this many runs without a window and print the scores, --simulate
+    pub simulate: Option<u32>,
+    // print the saved stats instead of playing, --stats
+    pub stats: bool,
+    // watch a recorded run instead of playing, --replay
+    pub replay: Option<String>,
+    // fly a handcrafted course before the random obstacles, --course
+    pub course: Option<String>,
+    // open the window fullscreen, --fullscreen
+    // bracket-lib's main loop keeps the window to itself once it runs,
+    // so this can only be chosen at launch, not toggled while playing
+    pub fullscreen: bool,
+    // allow the debug cheats, --debug
+    pub debug: bool,
+}
+
+impl Args {
+    // read the options from std::env::args, on top of the saved screen size
+    // bad or unknown options are reported and otherwise ignored
+    pub fn parse(screen: Screen) -> Self {
+        let mut args = Args {
+            screen,
+            seed: None,
+            simulate: None,
+            stats: false,
+            replay: None,
+            course: None,
+            fullscreen: false,
+            debug: false,
+        };
+        let mut options = std::env::args().skip(1);
+        while let Some(option) = options.next() {
+            match option.as_str() {
+                "--width" => {
+                    if let Some(width) = parse_number(&option, options.next(), MIN_WIDTH) {
+                        args.screen.width = width;
+                    }
+                }
+                "--height" => {
+                    if let Some(height) = parse_number(&option, options.next(), MIN_HEIGHT) {
+                        args.screen.height = height;
+                    }
+                }
+                "--seed" => match options.next().map(|value| value.parse::<u64>()) {
+                    Some(Ok(seed)) => args.seed = Some(seed),
+                    _ => eprintln!("warning: {option} needs a number"),
+                },
+                "--stats" => args.stats = true,
+                "--fullscreen" => args.fullscreen = true,
+                "--debug" => args.debug = true,
+                "--replay" => match options.next() {
+                    Some(path) => args.replay = Some(path),
+                    None => eprintln!("warning: {option} needs a file"),
+                },
+                "--course" => match options.next() {
+                    Some(path) => args.course = Some(path),
+                    None => eprintln!("warning: {option} needs a file"),
+                },
+                "--simulate" => match options.next().map(|value| value.parse::<u32>()) {
+                    Some(Ok(runs)) => args.simulate = Some(runs),
+                    _ => eprintln!("warning: {option} needs a number"),
+                },
+                _ => eprintln!("warning: unknown option {option}"),
+            }
+        }
+        args
+    }
+}
+
+// the number following an option, at least `min`
+fn parse_number(option: &str, value: Option<String>, min: i32) -> Option<i32> {
+    match value.map(|value| value.parse::<i32>()) {
+        Some(Ok(number)) if number >= min => Some(number),
+        Some(Ok(_)) => {
+            eprintln!("warning: {option} must be at least {min}");
+            None
+        }
+        _ => {
+            eprintln!("warning: {option} needs a number");
+            None
+        }
+    }
+}
@@ -0,0 +1,186 @@
+// sound effects and background music
+// only compiled in with the "audio" feature, without it every call is a no-op
+// so the rest of the game doesn't need to care whether sound is available
+
+#[cfg(feature = "audio")]
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+#[cfg(feature = "audio")]
+use std::{io::Cursor, sync::Arc};
+
+// played whenever the player flaps
+#[cfg(feature = "audio")]
+const FLAP_SOUND: &str = "resources/flap.wav";
+
+// played when the player crashes
+#[cfg(feature = "audio")]
+const CRASH_SOUND: &str = "resources/crash.wav";
+
+// looped while playing
+#[cfg(feature = "audio")]
+const MUSIC: &str = "resources/music.wav";
+
+#[cfg(feature = "audio")]
+pub struct Audio {
+    // the output stream has to stay alive for anything to be heard
+    _stream: Option<OutputStream>,
+    // handle used to start sounds on the stream
+    handle: Option<OutputStreamHandle>,
+    // sound files loaded once at startup
+    flap: Option<Arc<[u8]>>,
+    crash: Option<Arc<[u8]>>,
+    music: Option<Arc<[u8]>>,
+    // the looping music track, created the first time the game is played
+    // dropping it stops the music straight away, so quitting never waits on it
+    music_sink: Option<Sink>,
+    // true when everything is silenced
+    muted: bool,
+    // master volume from the settings, 0.0..=1.0
+    volume: f32,
+}
+
+#[cfg(not(feature = "audio"))]
+pub struct Audio;
+
+#[cfg(feature = "audio")]
+impl Audio {
+    // open the default output device and load the sounds
+    // problems are reported as warnings, the game just stays silent
+    pub fn new() -> Self {
+        let (stream, handle) = match OutputStream::try_default() {
+            Ok((stream, handle)) => (Some(stream), Some(handle)),
+            Err(err) => {
+                eprintln!("warning: no audio output available: {err}");
+                (None, None)
+            }
+        };
+        Audio {
+            _stream: stream,
+            handle,
+            flap: load_sound(FLAP_SOUND),
+            crash: load_sound(CRASH_SOUND),
+            music: load_sound(MUSIC),
+            music_sink: None,
+            muted: false,
+            volume: 1.0,
+        }
+    }
+
+    pub fn play_flap(&self) {
+        self.play(self.flap.as_ref());
+    }
+
+    pub fn play_crash(&self) {
+        self.play(self.crash.as_ref());
+    }
+
+    // resume the music, starting it from the beginning only the first time
+    // pausing keeps its position so it carries on after the next restart
+    pub fn set_music_playing(&mut self, playing: bool) {
+        if playing && self.music_sink.is_none() {
+            self.music_sink = self.start_music();
+        }
+        if let Some(sink) = &self.music_sink {
+            if playing && sink.is_paused() {
+                sink.play();
+            } else if !playing && !sink.is_paused() {
+                sink.pause();
+            }
+        }
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.update_music_volume();
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+        self.update_music_volume();
+    }
+
+    // volume everything is played at, taking mute into account
+    fn effective_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+
+    fn update_music_volume(&self) {
+        if let Some(sink) = &self.music_sink {
+            sink.set_volume(self.effective_volume());
+        }
+    }
+
+    fn start_music(&self) -> Option<Sink> {
+        let handle = self.handle.as_ref()?;
+        let music = self.music.as_ref()?;
+        let source = match Decoder::new(Cursor::new(Arc::clone(music))) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("warning: could not decode music: {err}");
+                return None;
+            }
+        };
+        match Sink::try_new(handle) {
+            Ok(sink) => {
+                sink.set_volume(self.effective_volume());
+                sink.append(source.repeat_infinite());
+                Some(sink)
+            }
+            Err(err) => {
+                eprintln!("warning: could not play music: {err}");
+                None
+            }
+        }
+    }
+
+    fn play(&self, sound: Option<&Arc<[u8]>>) {
+        if self.effective_volume() <= 0.0 {
+            return;
+        }
+        if let (Some(handle), Some(sound)) = (&self.handle, sound) {
+            match Decoder::new(Cursor::new(Arc::clone(sound))) {
+                Ok(source) => {
+                    if let Err(err) = handle.play_raw(source.amplify(self.volume).convert_samples())
+                    {
+                        eprintln!("warning: could not play sound: {err}");
+                    }
+                }
+                Err(err) => eprintln!("warning: could not decode sound: {err}"),
+            }
+        }
+    }
+}
+
+// same interface as above, doing nothing
+#[cfg(not(feature = "audio"))]
+#[allow(clippy::unused_self)]
+impl Audio {
+    pub fn new() -> Self {
+        Audio
+    }
+
+    pub fn play_flap(&self) {}
+
+    pub fn play_crash(&self) {}
+
+    pub fn set_music_playing(&mut self, _playing: bool) {}
+
+    pub fn set_muted(&mut self, _muted: bool) {}
+
+    pub fn set_volume(&mut self, _volume: f32) {}
+}
+
+// read a whole sound file into memory
+#[cfg(feature = "audio")]
+fn load_sound(path: &str) -> Option<Arc<[u8]>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Some(bytes.into()),
+        Err(err) => {
+            eprintln!("warning: could not load {path}: {err}");
+            None
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{draw, glyph, renderer::Renderer, theme::Theme};
+use bracket_lib::prelude::*;
+
+// player columns for one full day/night cycle
+pub const DAY_LENGTH: i32 = 800;
+
+// rows of ground along the bottom of the screen, deadly to touch
+pub const GROUND_ROWS: i32 = 1;
+
+// columns from one mark on the ground to the next
+const GROUND_MARK_SPACING: i32 = 4;
+
+// real seconds the scenery takes to fade from one biome to the next
+const BIOME_FADE_SECONDS: f32 = 2.0;
+
+// a stretch of the course with its own sky and scenery
+#[derive(Debug, PartialEq)]
+pub struct Biome {
+    // lowest score the biome is reached at
+    from_score: i32,
+    // color the theme's sky is tinted towards
+    tint: (u8, u8, u8),
+    // how much of the tint to mix in, 0.0 leaves the sky as it is
+    tint_amount: f32,
+    // glyph of every scenery layer, far first
+    glyphs: [FontCharType; 2],
+}
+
+// the biomes in the order the course goes through them
+const BIOMES: [Biome; 3] = [
+    // the theme's own sky, distant stars and closer clouds
+    Biome {
+        from_score: 0,
+        tint: BLACK,
+        tint_amount: 0.0,
+        glyphs: [glyph::STAR, glyph::CLOUD],
+    },
+    // an orange sky with low clouds
+    Biome {
+        from_score: 10,
+        tint: (200, 80, 40),
+        tint_amount: 0.4,
+        glyphs: [glyph::HAZE, glyph::LOW_CLOUD],
+    },
+    // out among the stars
+    Biome {
+        from_score: 25,
+        tint: BLACK,
+        tint_amount: 0.8,
+        glyphs: [glyph::STAR, glyph::BRIGHT_STAR],
+    },
+];
+
+// the biome the course is in at this score
+pub fn biome(score: i32) -> &'static Biome {
+    BIOMES
+        .iter()
+        .rev()
+        .find(|biome| score >= biome.from_score)
+        .unwrap_or(&BIOMES[0])
+}
+
+impl Biome {
+    // the sky of the theme, tinted for this biome
+    fn sky(&self, sky: RGB) -> RGB {
+        sky.lerp(RGB::named(self.tint), self.tint_amount)
+    }
+}
+
+// sky color at a point of the cycle, phase runs from 0.0 to 1.0
+// colors are blended so the sky never snaps from one to the next
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn sky_color(phase: f32, palette: &[(u8, u8, u8)]) -> RGB {
+    let position = phase.rem_euclid(1.0) * palette.len() as f32;
+    let index = position as usize % palette.len();
+    let next = (index + 1) % palette.len();
+    RGB::named(palette[index]).lerp(RGB::named(palette[next]), position.fract())
+}
+
+// a single decoration in a background layer
+struct Cell {
+    // column inside the layer, 0..width
+    x: i32,
+    // screen row
+    y: i32,
+}
+
+// one strip of decorations scrolling at a fraction of the player's speed
+struct Layer {
+    // the layer moves one column every `scroll_divisor` player columns
+    // 4 = 0.25x, 2 = 0.5x
+    scroll_divisor: i32,
+    // decorations, placed once and reused every frame
+    cells: Vec<Cell>,
+}
+
+// parallax scenery drawn behind the obstacles
+pub struct Background {
+    // far layers first so nearer ones are drawn on top
+    layers: Vec<Layer>,
+    // layers repeat every `width` columns
+    width: i32,
+    // the biome being faded to, and the one being faded from
+    biome: &'static Biome,
+    last_biome: &'static Biome,
+    // how far the fade has got, 1.0 once it is done
+    fade: f32,
+}
+
+impl Background {
+    // scenery for a playfield of the given size
+    pub fn new(width: i32, height: i32, random: &mut RandomNumberGenerator) -> Self {
+        let mut far = Layer::new(4);
+        far.scatter(random, 40, width, height);
+        let mut near = Layer::new(2);
+        near.scatter(random, 12, width, height);
+        Background {
+            layers: vec![far, near],
+            width,
+            biome: biome(0),
+            last_biome: biome(0),
+            fade: 1.0,
+        }
+    }
+
+    // back to the first biome for a new run, without a fade
+    pub fn reset(&mut self) {
+        self.biome = biome(0);
+        self.last_biome = self.biome;
+        self.fade = 1.0;
+    }
+
+    // start fading to the biome of the score, and carry on fading by dt real seconds
+    pub fn update(&mut self, score: i32, dt: f32) {
+        let biome = biome(score);
+        if biome != self.biome {
+            self.last_biome = self.biome;
+            self.biome = biome;
+            self.fade = 0.0;
+        }
+        self.fade = f32::min(1.0, self.fade + dt / BIOME_FADE_SECONDS);
+    }
+
+    // the theme's sky for this point of the day, tinted for the biome
+    pub fn sky(&self, sky: RGB) -> RGB {
+        self.last_biome
+            .sky(sky)
+            .lerp(self.biome.sky(sky), self.fade)
+    }
+
+    // the old scenery fades into the sky, then the new one fades out of it
+    pub fn render<R: Renderer>(&self, ctx: &mut R, player_x: i32, sky: RGB, theme: &Theme) {
+        let (biome, hidden) = if self.fade < 0.5 {
+            (self.last_biome, self.fade * 2.0)
+        } else {
+            (self.biome, 2.0 - self.fade * 2.0)
+        };
+        for ((layer, &color), &glyph) in self.layers.iter().zip(&theme.scenery).zip(&biome.glyphs) {
+            let color = RGB::named(color).lerp(sky, hidden);
+            // every layer repeats every `width` columns,
+            // so wrapping keeps it seamless however far the player flies
+            let offset = (player_x / layer.scroll_divisor).rem_euclid(self.width);
+            for cell in &layer.cells {
+                let screen_x = (cell.x - offset).rem_euclid(self.width);
+                draw::set(ctx, screen_x, cell.y, color, sky, glyph);
+            }
+        }
+    }
+}
+
+// the ground scrolls at the speed of the obstacles, a column per
+// player column, one cell drawn per column and row so it costs next to nothing
+pub fn render_ground<R: Renderer>(ctx: &mut R, player_x: i32, theme: &Theme) {
+    let (width, height) = ctx.size();
+    for y in height - GROUND_ROWS..height {
+        for x in 0..width {
+            let glyph = if (player_x + x).rem_euclid(GROUND_MARK_SPACING) == 0 {
+                glyph::GROUND_MARK
+            } else {
+                glyph::GROUND
+            };
+            ctx.set(x, y, theme.ground, BLACK, glyph);
+        }
+    }
+}
+
+impl Layer {
+    fn new(scroll_divisor: i32) -> Self {
+        Layer {
+            scroll_divisor,
+            cells: Vec::new(),
+        }
+    }
+
+    // place `count` decorations at random across the playfield
+    fn scatter(
+        &mut self,
+        random: &mut RandomNumberGenerator,
+        count: usize,
+        width: i32,
+        height: i32,
+    ) {
+        self.cells = (0..count)
+            .map(|_| Cell {
+                x: random.range(0, width),
+                y: random.range(0, height),
+            })
+            .collect();
+    }
+}
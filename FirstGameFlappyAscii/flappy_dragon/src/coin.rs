@@ -0,0 +1,57 @@
+use crate::{
+    draw, glyph,
+    player::Player,
+    renderer::{Renderable, Renderer},
+    theme::Theme,
+    update::Update,
+};
+use bracket_lib::prelude::*;
+
+// a coin floating in an obstacle gap, worth bonus points
+#[derive(Debug)]
+pub struct Coin {
+    // world-space column, like obstacles
+    pub x: i32,
+    // screen row
+    pub y: i32,
+}
+
+impl Coin {
+    pub fn new(x: i32, y: i32) -> Self {
+        Coin { x, y }
+    }
+
+    // move one cell towards the player if it is within radius cells,
+    // never behind the player so it can still be flown through
+    pub fn pull_towards(&mut self, player: &Player, radius: i32) {
+        if (self.x - player.x).abs() > radius || (self.y - player.y).abs() > radius {
+            return;
+        }
+        if self.x > player.x {
+            self.x -= 1;
+        }
+        self.y += (player.y - self.y).signum();
+    }
+
+    // the player collects a coin by flying through its cell
+    // one row of slack because the player can move two rows in a frame
+    pub fn touches(&self, player: &Player) -> bool {
+        self.x == player.x && (self.y - player.y).abs() <= 1
+    }
+}
+
+// only scrolls past
+impl Update for Coin {}
+
+impl Renderable for Coin {
+    fn render<R: Renderer>(&self, ctx: &mut R, player_x: i32, theme: &Theme) {
+        draw::set(
+            ctx,
+            self.x - player_x,
+            self.y,
+            theme.coin,
+            BLACK,
+            glyph::COIN,
+        );
+    }
+}
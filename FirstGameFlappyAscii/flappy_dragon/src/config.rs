@@ -0,0 +1,184 @@
+use crate::{player::PhysicsConfig, Difficulty};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// default game screen width
+// const usage
+pub const SCREEN_WIDTH: i32 = 80;
+
+// default game screen height
+pub const SCREEN_HEIGHT: i32 = 50;
+
+// size of the playfield in console cells, can be changed on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Screen {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Default for Screen {
+    // the classic 80x50 terminal
+    fn default() -> Self {
+        Screen {
+            width: SCREEN_WIDTH,
+            height: SCREEN_HEIGHT,
+        }
+    }
+}
+
+impl fmt::Display for Screen {
+    // WIDTHxHEIGHT, the way --width and --height are usually talked about
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+// default frame duration: float type
+pub const FRAME_DURATION: f32 = 75.0;
+
+// world-space columns between two consecutive obstacles
+pub const OBSTACLE_SPACING: i32 = 30;
+
+// points it takes to go up a speed level
+const POINTS_PER_SPEED_LEVEL: i32 = 10;
+
+// fastest speed level, the floor that keeps long runs playable
+const MAX_SPEED_LEVEL: i32 = 9;
+
+// part of the frame duration every speed level above the first takes off
+const SPEED_STEP: f32 = 0.05;
+
+// cells the screen jolts by on a crash
+const SHAKE_INTENSITY: f32 = 2.0;
+
+// cells around the player that can be seen on a fog run
+const FOG_RADIUS: i32 = 12;
+
+// real milliseconds per physics frame of a hardcore run
+const HARDCORE_FRAME_DURATION: f32 = 45.0;
+
+// gap size a hardcore run starts with
+const HARDCORE_GAP: i32 = 11;
+
+// physics frames a single real frame can catch up on
+const MAX_STEPS_PER_FRAME: u32 = 5;
+
+// how the score of a run is counted, picked on the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ScoringMode {
+    // a point per obstacle passed plus the coins, the original scoring
+    #[default]
+    Obstacles,
+    // a point per column flown
+    Distance,
+    // only the coins collected count
+    Coins,
+}
+
+impl ScoringMode {
+    // the next scoring, wrapping around to Obstacles
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            ScoringMode::Obstacles => ScoringMode::Distance,
+            ScoringMode::Distance => ScoringMode::Coins,
+            ScoringMode::Coins => ScoringMode::Obstacles,
+        }
+    }
+
+    // the file a record is kept in under this scoring, so a score is only
+    // ever compared with scores counted the same way
+    // obstacle records keep the file they had before there was a choice
+    #[must_use]
+    pub fn records_file(self, file: &str) -> String {
+        match self {
+            ScoringMode::Obstacles => file.to_string(),
+            ScoringMode::Distance => format!("distance_{file}"),
+            ScoringMode::Coins => format!("coins_{file}"),
+        }
+    }
+}
+
+// every tunable of a run in one place
+// the default is the Normal difficulty on the classic 80x50 screen
+#[derive(Debug, Clone, Copy)]
+pub struct GameConfig {
+    // size of the playfield
+    pub screen: Screen,
+    // real milliseconds per physics frame, lower scrolls faster
+    pub frame_duration: f32,
+    // gravity, flap strength and the fastest fall
+    pub physics: PhysicsConfig,
+    // gap size before the score starts shrinking it
+    pub base_gap: i32,
+    // world-space columns between two consecutive obstacles
+    pub obstacle_spacing: i32,
+    // cells the screen jolts by on a crash, half that on a hit that
+    // costs a life or the shield, 0.0 turns the shake off
+    pub shake_intensity: f32,
+    // cells around the player that can be seen on a fog run,
+    // everything further away is dark apart from the HUD
+    pub fog_radius: i32,
+    // physics frames one real frame steps at most, after a stall like
+    // dragging the window the time beyond that is dropped instead of
+    // the world jumping ahead all at once
+    pub max_steps_per_frame: u32,
+    // what a run's score counts
+    pub scoring: ScoringMode,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig::new(Screen::default(), Difficulty::Normal)
+    }
+}
+
+impl GameConfig {
+    // the tunables of a difficulty, on a screen of the given size
+    #[must_use]
+    pub fn new(screen: Screen, difficulty: Difficulty) -> Self {
+        let mut config = GameConfig {
+            screen,
+            frame_duration: 0.0,
+            physics: PhysicsConfig::default(),
+            base_gap: 0,
+            obstacle_spacing: OBSTACLE_SPACING,
+            shake_intensity: SHAKE_INTENSITY,
+            fog_radius: FOG_RADIUS,
+            max_steps_per_frame: MAX_STEPS_PER_FRAME,
+            scoring: ScoringMode::Obstacles,
+        };
+        config.set_difficulty(difficulty);
+        config
+    }
+
+    // switch to the tunables of another difficulty, the screen stays as it is
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.frame_duration = difficulty.frame_duration();
+        self.physics = difficulty.physics();
+        self.base_gap = difficulty.base_gap();
+        self.obstacle_spacing = difficulty.obstacle_spacing();
+    }
+
+    // the tunables of a hardcore run, Hard only faster and with smaller gaps
+    pub fn set_hardcore(&mut self) {
+        self.set_difficulty(Difficulty::Hard);
+        self.frame_duration = HARDCORE_FRAME_DURATION;
+        self.base_gap = HARDCORE_GAP;
+    }
+
+    // 1 at the start of a run, one level up every POINTS_PER_SPEED_LEVEL points
+    #[must_use]
+    pub fn speed_level(score: i32) -> i32 {
+        1 + (score / POINTS_PER_SPEED_LEVEL).clamp(0, MAX_SPEED_LEVEL - 1)
+    }
+
+    // frame_duration shortened by the speed level the score has reached,
+    // at most MAX_SPEED_LEVEL levels, so the world never scrolls too fast to react
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn frame_duration_for_score(&self, score: i32) -> f32 {
+        let levels = (GameConfig::speed_level(score) - 1) as f32;
+        self.frame_duration * (1.0 - SPEED_STEP * levels)
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+// one obstacle of a handcrafted course
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CourseObstacle {
+    // row of the middle of the gap
+    pub gap_y: i32,
+    // rows the gap is across
+    pub size: i32,
+}
+
+// read a course for --course, one "GAP_Y SIZE" line per obstacle in the
+// order they come up, blank lines and lines starting with # are skipped
+// anything else fails the whole file, naming the line, so a typo in a
+// designed level doesn't go unnoticed
+pub fn load_course(path: &str) -> Result<Vec<CourseObstacle>, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let mut course = Vec::new();
+    for (number, line) in (1..).zip(text.lines()) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace().map(str::parse::<i32>);
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(gap_y)), Some(Ok(size)), None) => course.push(CourseObstacle { gap_y, size }),
+            _ => return Err(format!("line {number} should be GAP_Y SIZE: {line}")),
+        }
+    }
+    Ok(course)
+}
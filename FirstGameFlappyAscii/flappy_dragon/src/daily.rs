@@ -0,0 +1,110 @@
+use crate::config::{ScoringMode, Screen};
+use std::{
+    convert::TryFrom,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// plain-text file with the "YYYYMMDD WIDTHxHEIGHT SCORE" of the best
+// daily challenge run, the course is only the same on the same screen size
+const DAILY_FILE: &str = "daily.txt";
+
+// a day of the calendar, in UTC so everyone gets the same challenge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Date {
+    pub year: i64,
+    pub month: i64,
+    pub day: i64,
+}
+
+impl Date {
+    // today's date, a clock set before 1970 counts as 1970-01-01
+    pub fn today() -> Self {
+        Date::from_days(i64::try_from(unix_seconds() / 86_400).unwrap_or_default())
+    }
+
+    // the date `days` days after 1970-01-01
+    // the usual days-to-civil conversion, counting years from March
+    // so the leap day is the last day of the year
+    fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let march_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * march_month + 2) / 5 + 1;
+        let month = if march_month < 10 {
+            march_month + 3
+        } else {
+            march_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date { year, month, day }
+    }
+
+    // YYYYMMDD, the seed of that day's challenge
+    pub fn number(self) -> i64 {
+        self.year * 10_000 + self.month * 100 + self.day
+    }
+
+    pub fn seed(self) -> u64 {
+        u64::try_from(self.number()).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Date {
+    // YYYY-MM-DD
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// seconds since 1970-01-01, 0 for a clock set before that
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+// the time now as YYYY-MM-DD_HH-MM-SS in UTC, safe to put in a file name
+pub fn timestamp() -> String {
+    let time = unix_seconds() % 86_400;
+    format!(
+        "{}_{:02}-{:02}-{:02}",
+        Date::today(),
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+// the best score of the given day's challenge on a screen size under a scoring
+// a score saved on another day or screen size, or a missing or corrupt file,
+// counts as 0
+pub fn load_daily_best(date: Date, screen: Screen, scoring: ScoringMode) -> i32 {
+    let text = std::fs::read_to_string(scoring.records_file(DAILY_FILE)).unwrap_or_default();
+    let mut parts = text.split_whitespace();
+    match (
+        parts.next().and_then(|day| day.parse::<i64>().ok()),
+        parts.next(),
+        parts.next().and_then(|score| score.parse().ok()),
+    ) {
+        (Some(day), Some(size), Some(score))
+            if day == date.number() && size == screen.to_string() =>
+        {
+            score
+        }
+        _ => 0,
+    }
+}
+
+pub fn save_daily_best(date: Date, screen: Screen, score: i32, scoring: ScoringMode) {
+    let text = format!("{} {screen} {score}", date.number());
+    let file = scoring.records_file(DAILY_FILE);
+    if let Err(err) = std::fs::write(&file, text) {
+        eprintln!("Could not save daily best to {file}: {err}");
+    }
+}
@@ -0,0 +1,76 @@
+use crate::renderer::Renderer;
+use bracket_lib::prelude::*;
+
+// whether x, y is a cell of a width x height console
+pub fn in_bounds(x: i32, y: i32, width: i32, height: i32) -> bool {
+    (0..width).contains(&x) && (0..height).contains(&y)
+}
+
+// ctx.set for anything that moves, cells off the console are skipped
+// world-space things end up left or right of the screen all the time,
+// and nothing should depend on bracket-lib quietly ignoring them
+pub fn set<R: Renderer, FG: Into<RGBA>, BG: Into<RGBA>>(
+    ctx: &mut R,
+    x: i32,
+    y: i32,
+    fg: FG,
+    bg: BG,
+    glyph: FontCharType,
+) {
+    let (width, height) = ctx.size();
+    if in_bounds(x, y, width, height) {
+        ctx.set(x, y, fg, bg, glyph);
+    }
+}
+
+// ctx.set_bg, with the same bounds check as set
+pub fn set_bg<R: Renderer, BG: Into<RGBA>>(ctx: &mut R, x: i32, y: i32, bg: BG) {
+    let (width, height) = ctx.size();
+    if in_bounds(x, y, width, height) {
+        ctx.set_bg(x, y, bg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::{Offset, Recorder};
+
+    #[test]
+    fn only_the_cells_of_the_console_are_in_bounds() {
+        assert!(in_bounds(0, 0, 80, 50));
+        assert!(in_bounds(79, 49, 80, 50));
+        for &(x, y) in &[(-1, 0), (0, -1), (80, 0), (0, 50), (i32::MIN, i32::MAX)] {
+            assert!(!in_bounds(x, y, 80, 50), "{}, {}", x, y);
+        }
+    }
+
+    // every cell in and around the console, the recorder panics
+    // at the first one that gets through off it
+    #[test]
+    fn cells_off_the_console_are_skipped() {
+        let mut ctx = Recorder::new(8, 5);
+        for x in -3..11 {
+            for y in -3..8 {
+                set(&mut ctx, x, y, WHITE, BLACK, to_cp437('#'));
+                set_bg(&mut ctx, x, y, BLACK);
+            }
+        }
+        assert_eq!(ctx.cells.len(), 8 * 5);
+        assert_eq!(ctx.backgrounds.len(), 8 * 5);
+        assert!(ctx.cells.contains(&(0, 0, to_cp437('#'))));
+        assert!(ctx.cells.contains(&(7, 4, to_cp437('#'))));
+    }
+
+    // the screen shake moves cells after the bounds check of the caller,
+    // so it has to check again
+    #[test]
+    fn shaken_cells_off_the_console_are_skipped() {
+        let mut ctx = Recorder::new(8, 5);
+        let mut shaken = Offset::new(&mut ctx, -2, 1);
+        set(&mut shaken, 0, 0, WHITE, BLACK, to_cp437('#'));
+        set(&mut shaken, 2, 4, WHITE, BLACK, to_cp437('#'));
+        set(&mut shaken, 2, 3, WHITE, BLACK, to_cp437('#'));
+        assert_eq!(ctx.cells, vec![(0, 4, to_cp437('#'))]);
+    }
+}
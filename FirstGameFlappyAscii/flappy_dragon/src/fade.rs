@@ -0,0 +1,59 @@
+use crate::State;
+use bracket_lib::prelude::*;
+
+// real seconds the screen takes to go black, and as long again to come back
+const FADE_SECONDS: f32 = 0.25;
+
+// a fade to black and back between two screens,
+// the screen changes while everything is black
+pub struct Transition {
+    // real seconds since the fade started
+    elapsed: f32,
+    // the change of screen, taken once it has been made
+    then: Option<fn(&mut State)>,
+}
+
+impl Transition {
+    pub fn new(then: fn(&mut State)) -> Self {
+        Transition {
+            elapsed: 0.0,
+            then: Some(then),
+        }
+    }
+
+    // advance by dt real seconds, the change of screen is handed back
+    // on the frame the screen goes black
+    pub fn update(&mut self, dt: f32) -> Option<fn(&mut State)> {
+        self.elapsed += dt;
+        if self.elapsed >= FADE_SECONDS {
+            self.then.take()
+        } else {
+            None
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= 2.0 * FADE_SECONDS
+    }
+
+    // how dark the screen is, 0.0 as drawn and 1.0 black
+    pub fn darkness(&self) -> f32 {
+        (1.0 - (self.elapsed / FADE_SECONDS - 1.0).abs()).clamp(0.0, 1.0)
+    }
+}
+
+// darken everything drawn this frame, 0.0 leaves it as it is and 1.0 is black
+// bracket-lib keeps the cells of its consoles to itself behind a lock,
+// so this works on them directly instead of through the Renderer
+pub fn darken(amount: f32) {
+    let black = RGBA::from_u8(0, 0, 0, 255);
+    for display in &mut BACKEND_INTERNAL.lock().consoles {
+        if let Some(console) = display.console.as_any_mut().downcast_mut::<SimpleConsole>() {
+            for tile in &mut console.tiles {
+                tile.fg = tile.fg.lerp(black, amount);
+                tile.bg = tile.bg.lerp(black, amount);
+            }
+            console.is_dirty = true;
+        }
+    }
+}
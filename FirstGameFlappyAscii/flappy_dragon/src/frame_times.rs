@@ -0,0 +1,47 @@
+// frames the smoothed frame time is averaged over, about half a second at 60 FPS
+const SAMPLES: usize = 30;
+
+// the real milliseconds of the last few frames, for a frame rate
+// that can be read instead of one that jitters every frame
+pub struct FrameTimes {
+    // oldest samples get overwritten once it is full
+    samples: [f32; SAMPLES],
+    // where the next sample goes
+    next: usize,
+    // samples taken so far, up to SAMPLES
+    count: usize,
+    // the newest sample, as it came in
+    latest: f32,
+}
+
+impl FrameTimes {
+    pub fn new() -> Self {
+        FrameTimes {
+            samples: [0.0; SAMPLES],
+            next: 0,
+            count: 0,
+            latest: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, frame_time_ms: f32) {
+        self.samples[self.next] = frame_time_ms;
+        self.next = (self.next + 1) % SAMPLES;
+        self.count = usize::min(SAMPLES, self.count + 1);
+        self.latest = frame_time_ms;
+    }
+
+    // the last frame's time, jitter and all
+    pub fn latest(&self) -> f32 {
+        self.latest
+    }
+
+    // the mean of the samples taken, 0.0 before the first one
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average(&self) -> f32 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.samples[..self.count].iter().sum::<f32>() / self.count as f32
+    }
+}
@@ -0,0 +1,74 @@
+// controller input
+// only compiled in with the "gamepad" feature, without it no button is ever
+// pressed so the rest of the game doesn't need to care whether gilrs is there
+
+#[cfg(feature = "gamepad")]
+use gilrs::{EventType, Gilrs};
+
+// the buttons the game listens to
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+pub enum Button {
+    // A on most controllers
+    South,
+    // B on most controllers
+    East,
+    Start,
+}
+
+#[cfg(feature = "gamepad")]
+pub struct Gamepad {
+    // None when gilrs couldn't be started, the game then runs keyboard-only
+    gilrs: Option<Gilrs>,
+}
+
+#[cfg(not(feature = "gamepad"))]
+pub struct Gamepad;
+
+#[cfg(feature = "gamepad")]
+impl Gamepad {
+    // problems are reported as a warning, the keyboard keeps working
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(err) => {
+                eprintln!("warning: no gamepad support available: {err}");
+                None
+            }
+        };
+        Gamepad { gilrs }
+    }
+
+    // the first button pressed on any controller since the last call
+    // no controller connected simply means no button
+    pub fn poll(&mut self) -> Option<Button> {
+        let gilrs = self.gilrs.as_mut()?;
+        let mut pressed = None;
+        // drain every event so old presses don't pile up
+        while let Some(event) = gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                let button = match button {
+                    gilrs::Button::South => Some(Button::South),
+                    gilrs::Button::East => Some(Button::East),
+                    gilrs::Button::Start => Some(Button::Start),
+                    _ => None,
+                };
+                pressed = pressed.or(button);
+            }
+        }
+        pressed
+    }
+}
+
+// same interface as above, doing nothing
+#[cfg(not(feature = "gamepad"))]
+#[allow(clippy::unused_self)]
+impl Gamepad {
+    pub fn new() -> Self {
+        Gamepad
+    }
+
+    pub fn poll(&mut self) -> Option<Button> {
+        None
+    }
+}
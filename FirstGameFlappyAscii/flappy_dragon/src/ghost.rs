@@ -0,0 +1,26 @@
+use crate::config::ScoringMode;
+use std::fmt::Write;
+
+// plain-text file with the player's row on every physics frame of the best run
+const GHOST_FILE: &str = "ghost.txt";
+
+// read the best run of a scoring back, one row per line
+// a missing file is no ghost and a malformed line ends the trace there
+pub fn load_ghost(scoring: ScoringMode) -> Vec<i32> {
+    let text = std::fs::read_to_string(scoring.records_file(GHOST_FILE)).unwrap_or_default();
+    text.lines()
+        .map_while(|line| line.trim().parse().ok())
+        .collect()
+}
+
+pub fn save_ghost(trace: &[i32], scoring: ScoringMode) {
+    let mut text = String::new();
+    for y in trace {
+        // writing to a String can't fail
+        let _ = writeln!(text, "{y}");
+    }
+    let file = scoring.records_file(GHOST_FILE);
+    if let Err(err) = std::fs::write(&file, text) {
+        eprintln!("Could not save ghost to {file}: {err}");
+    }
+}
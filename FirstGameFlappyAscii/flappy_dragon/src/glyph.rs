@@ -0,0 +1,47 @@
+// every glyph the game draws, looked up in the code page once
+// instead of going through to_cp437 for every cell of every frame
+// only text printed from a String still goes through to_cp437
+use bracket_lib::prelude::FontCharType;
+
+// plain ASCII sits at the same place in code page 437,
+// to_cp437 isn't a const fn so it can't be used here
+const fn ascii(c: u8) -> FontCharType {
+    c as FontCharType
+}
+
+// the player, wings resting and right after a flap
+pub const PLAYER: FontCharType = ascii(b'@');
+pub const PLAYER_FLAP: FontCharType = ascii(b'^');
+// the best run, raced alongside the player
+pub const GHOST: FontCharType = ascii(b'@');
+
+pub const WALL: FontCharType = ascii(b'|');
+pub const BOSS_WALL: FontCharType = ascii(b'#');
+// spikes point away from whatever they grow out of
+pub const SPIKES_UP: FontCharType = ascii(b'^');
+pub const SPIKES_DOWN: FontCharType = ascii(b'v');
+
+pub const COIN: FontCharType = ascii(b'$');
+pub const SHIELD: FontCharType = ascii(b'O');
+pub const SLOWMO: FontCharType = ascii(b'Z');
+pub const GRAVITY_FLIP: FontCharType = ascii(b'V');
+pub const MAGNET: FontCharType = ascii(b'U');
+
+// an explosion particle, fresh and dying
+pub const SPARK: FontCharType = ascii(b'*');
+pub const EMBER: FontCharType = ascii(b'.');
+
+// a life left in the HUD, '♥'
+pub const HEART: FontCharType = 3;
+pub const BLANK: FontCharType = ascii(b' ');
+
+// scenery layers of the biomes
+pub const STAR: FontCharType = ascii(b'.');
+pub const CLOUD: FontCharType = ascii(b'~');
+pub const HAZE: FontCharType = ascii(b'\'');
+pub const LOW_CLOUD: FontCharType = ascii(b'-');
+pub const BRIGHT_STAR: FontCharType = ascii(b'*');
+
+// the ground along the bottom, a mark every few columns shows it moving
+pub const GROUND: FontCharType = ascii(b'=');
+pub const GROUND_MARK: FontCharType = ascii(b'#');
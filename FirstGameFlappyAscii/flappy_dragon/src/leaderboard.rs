@@ -0,0 +1,93 @@
+use crate::config::ScoringMode;
+use bracket_lib::prelude::*;
+use std::{cmp::Reverse, fmt::Write};
+
+// plain-text file with one "INITIALS SCORE" line per entry
+const LEADERBOARD_FILE: &str = "leaderboard.txt";
+
+// how many scores are kept
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// how many letters a name can have
+pub const INITIALS_LENGTH: usize = 3;
+
+// read the saved top scores of a scoring, best first
+// malformed lines are skipped and a missing file is an empty board
+pub fn load_leaderboard(scoring: ScoringMode) -> Vec<(String, i32)> {
+    let text = std::fs::read_to_string(scoring.records_file(LEADERBOARD_FILE)).unwrap_or_default();
+    let mut board: Vec<(String, i32)> = text
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let score = parts.next()?.parse().ok()?;
+            Some((name.to_string(), score))
+        })
+        .collect();
+    sort_and_truncate(&mut board);
+    board
+}
+
+pub fn save_leaderboard(board: &[(String, i32)], scoring: ScoringMode) {
+    let mut text = String::new();
+    for (name, score) in board {
+        // writing to a String can't fail
+        let _ = writeln!(text, "{name} {score}");
+    }
+    let file = scoring.records_file(LEADERBOARD_FILE);
+    if let Err(err) = std::fs::write(&file, text) {
+        eprintln!("Could not save leaderboard to {file}: {err}");
+    }
+}
+
+// true if the score would make it onto the board
+pub fn qualifies(board: &[(String, i32)], score: i32) -> bool {
+    score > 0
+        && (board.len() < LEADERBOARD_SIZE || board.last().is_none_or(|entry| score > entry.1))
+}
+
+// add a new entry, keeping the board sorted and at most LEADERBOARD_SIZE long
+pub fn insert(board: &mut Vec<(String, i32)>, name: String, score: i32) {
+    board.push((name, score));
+    sort_and_truncate(board);
+}
+
+// a stable sort, so older entries stay ahead of newer ties
+fn sort_and_truncate(board: &mut Vec<(String, i32)>) {
+    board.sort_by_key(|entry| Reverse(entry.1));
+    board.truncate(LEADERBOARD_SIZE);
+}
+
+// the letter typed by a key, None for anything that isn't A-Z
+pub fn letter_for_key(key: VirtualKeyCode) -> Option<char> {
+    let letter = match key {
+        VirtualKeyCode::A => 'A',
+        VirtualKeyCode::B => 'B',
+        VirtualKeyCode::C => 'C',
+        VirtualKeyCode::D => 'D',
+        VirtualKeyCode::E => 'E',
+        VirtualKeyCode::F => 'F',
+        VirtualKeyCode::G => 'G',
+        VirtualKeyCode::H => 'H',
+        VirtualKeyCode::I => 'I',
+        VirtualKeyCode::J => 'J',
+        VirtualKeyCode::K => 'K',
+        VirtualKeyCode::L => 'L',
+        VirtualKeyCode::M => 'M',
+        VirtualKeyCode::N => 'N',
+        VirtualKeyCode::O => 'O',
+        VirtualKeyCode::P => 'P',
+        VirtualKeyCode::Q => 'Q',
+        VirtualKeyCode::R => 'R',
+        VirtualKeyCode::S => 'S',
+        VirtualKeyCode::T => 'T',
+        VirtualKeyCode::U => 'U',
+        VirtualKeyCode::V => 'V',
+        VirtualKeyCode::W => 'W',
+        VirtualKeyCode::X => 'X',
+        VirtualKeyCode::Y => 'Y',
+        VirtualKeyCode::Z => 'Z',
+        _ => return None,
+    };
+    Some(letter)
+}
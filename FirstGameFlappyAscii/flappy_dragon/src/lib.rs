@@ -0,0 +1,3562 @@
+// warn strict grammar
+#![warn(clippy::pedantic, clippy::all)]
+// warn unused mut
+#![warn(unused_mut)]
+// warn unused code
+#![warn(dead_code)]
+use bracket_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt::Write};
+
+mod achievements;
+mod args;
+mod audio;
+mod background;
+mod coin;
+mod config;
+mod course;
+mod daily;
+mod draw;
+mod fade;
+mod frame_times;
+mod gamepad;
+mod ghost;
+mod glyph;
+mod leaderboard;
+mod obstacle;
+mod particle;
+mod player;
+mod popup;
+mod powerup;
+mod renderer;
+mod replay;
+mod score_code;
+mod settings;
+mod simulate;
+mod stats;
+mod theme;
+mod update;
+use achievements::{Achievement, Achievements};
+use args::Args;
+use audio::Audio;
+use background::{render_ground, sky_color, Background, DAY_LENGTH, GROUND_ROWS};
+use coin::Coin;
+pub use config::{GameConfig, ScoringMode, Screen};
+use config::{FRAME_DURATION, OBSTACLE_SPACING};
+use course::{load_course, CourseObstacle};
+use daily::{load_daily_best, save_daily_best, timestamp, Date};
+use fade::{darken, Transition};
+use frame_times::FrameTimes;
+use gamepad::{Button, Gamepad};
+use ghost::{load_ghost, save_ghost};
+use leaderboard::{
+    insert, letter_for_key, load_leaderboard, qualifies, save_leaderboard, INITIALS_LENGTH,
+};
+pub use obstacle::{Obstacle, ObstacleKind};
+use particle::Particle;
+pub use player::{PhysicsConfig, Player};
+use popup::ScorePopup;
+use powerup::{PowerUp, PowerUpKind};
+use renderer::{render_all, Offset, Renderable, Renderer};
+use replay::Replay;
+use score_code::{char_for_key, ScoreCode, MAX_CODE_LENGTH};
+use settings::Settings;
+use simulate::should_flap;
+pub use simulate::simulate;
+use stats::Stats;
+use theme::{Theme, HIGH_CONTRAST};
+use update::update_all;
+
+// one in this many new obstacles comes with a power-up in front of it
+const POWERUP_CHANCE: i32 = 5;
+
+// one in this many new obstacles has a coin in its gap
+const COIN_CHANCE: i32 = 2;
+
+// points every collected coin is worth
+const COIN_VALUE: i32 = 2;
+
+// a pass within this many rows of a gap edge counts as close
+const CLOSE_PASS_MARGIN: i32 = 1;
+
+// highest score multiplier a combo can reach
+const MAX_MULTIPLIER: i32 = 5;
+
+// physics frames the multiplier flashes in the HUD after going up
+const MULTIPLIER_FLASH_FRAMES: i32 = 12;
+
+// a pass within this many rows of a gap edge is a near miss
+const NEAR_MISS_MARGIN: i32 = 2;
+
+// extra points for a near miss, on top of the points for passing
+const NEAR_MISS_BONUS: i32 = 3;
+
+// a boss obstacle shows up every time the score passes a multiple of this
+const BOSS_EVERY: i32 = 25;
+
+// extra points for getting past a boss
+const BOSS_BONUS: i32 = 5;
+
+// screen column score popups float up in, just ahead of the player
+const POPUP_X: i32 = 2;
+
+// seconds a slow-mo power-up lasts
+const SLOWMO_SECONDS: f32 = 5.0;
+
+// how much longer every physics frame takes during slow-mo
+const SLOWMO_FACTOR: f32 = 2.0;
+
+// seconds a gravity flip power-up lasts
+const FLIP_SECONDS: f32 = 5.0;
+
+// seconds a magnet power-up lasts
+const MAGNET_SECONDS: f32 = 8.0;
+
+// cells away a coin can be and still be pulled in by the magnet
+const MAGNET_RADIUS: i32 = 8;
+
+// how far the pause menu darkens the game behind it, 1.0 is black
+const PAUSE_DIM: f32 = 0.6;
+
+// columns across the pause menu's box
+const PAUSE_BOX_WIDTH: i32 = 24;
+
+// seconds counted down before a run starts
+const COUNTDOWN_SECONDS: f32 = 3.0;
+
+// one in this many physics frames starts a gust of wind
+const WIND_CHANCE: i32 = 150;
+
+// velocity a gust adds every physics frame, a fraction of gravity
+const WIND_STRENGTH: f32 = 0.03;
+
+// seconds a gust of wind blows for
+const WIND_SECONDS: f32 = 3.0;
+
+// milliseconds between physics frames in the tutorial, slower than Easy
+const TUTORIAL_FRAME_DURATION: f32 = 120.0;
+
+// real seconds "Well done!" is shown before going back to the menu
+const TUTORIAL_DONE_SECONDS: f32 = 2.0;
+
+// real seconds the menu waits for a key before the demo starts
+const DEMO_IDLE_SECONDS: f32 = 15.0;
+
+// real seconds the title is shown at launch before the menu
+const SPLASH_SECONDS: f32 = 2.0;
+
+// how fast the dragon on the title bobs, in radians per real second
+const SPLASH_BOB_SPEED: f32 = 6.0;
+
+// the dragon on the title, every line as wide so they stay lined up
+const SPLASH_ART: [&str; 4] = [
+    r"     ____     ",
+    r"  __/ o  \__> ",
+    r" <__      __) ",
+    r"    \_/\_/    ",
+];
+
+// real seconds the crash explosion plays before the death screen
+const DEATH_SECONDS: f32 = 0.5;
+
+// sparks thrown out by the crash explosion
+const DEATH_PARTICLES: usize = 20;
+
+// cells per real second the screen shake dies down by
+const SHAKE_DECAY: f32 = 8.0;
+
+// real seconds an unlocked achievement is announced for
+const TOAST_SECONDS: f32 = 3.0;
+
+// real seconds the result of taking a screenshot is shown for
+const SCREENSHOT_MESSAGE_SECONDS: f32 = 3.0;
+
+// column every run starts in
+const START_X: i32 = 5;
+
+// plain-text file holding the best score between runs
+const HIGH_SCORE_FILE: &str = "highscore.txt";
+
+// the best hardcore score, kept apart from the high score
+const HARDCORE_FILE: &str = "hardcore.txt";
+
+// the best time attack score, kept apart like the hardcore one
+const TIME_ATTACK_FILE: &str = "timeattack.txt";
+
+// everyone flies the daily challenge on this difficulty,
+// whatever the settings say, so they all get the same course
+const DAILY_DIFFICULTY: Difficulty = Difficulty::Normal;
+
+// real seconds a time attack run lasts
+const TIME_ATTACK_SECONDS: f32 = 60.0;
+
+// real seconds a crash takes off the time attack clock
+const TIME_ATTACK_PENALTY: f32 = 5.0;
+
+// keys used to control the game, remapped in the settings file
+#[derive(Debug, Clone, Copy)]
+pub struct KeyBindings {
+    // flap while playing
+    flap: VirtualKeyCode,
+    // flap for the second player of a race
+    flap2: VirtualKeyCode,
+    // pause and resume while playing
+    pause: VirtualKeyCode,
+    // start a new game from the menu or death screen
+    play: VirtualKeyCode,
+    // quit from the menu or death screen
+    quit: VirtualKeyCode,
+    // mute and unmute all sound, works everywhere
+    mute: VirtualKeyCode,
+    // show and hide the frame rate, works everywhere
+    fps: VirtualKeyCode,
+    // save the frame to a PNG, works everywhere
+    screenshot: VirtualKeyCode,
+}
+
+impl Default for KeyBindings {
+    // the original controls
+    fn default() -> Self {
+        KeyBindings {
+            flap: VirtualKeyCode::Space,
+            flap2: VirtualKeyCode::Up,
+            pause: VirtualKeyCode::Escape,
+            play: VirtualKeyCode::P,
+            quit: VirtualKeyCode::Q,
+            mute: VirtualKeyCode::M,
+            fps: VirtualKeyCode::F1,
+            screenshot: VirtualKeyCode::F12,
+        }
+    }
+}
+
+// how hard the game is, chosen on the menu
+// saved by name, "Easy", "Normal" or "Hard"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // how the player moves, only gravity changes between difficulties
+    fn physics(self) -> PhysicsConfig {
+        let gravity = match self {
+            Difficulty::Easy => 0.08,
+            Difficulty::Normal => 0.1,
+            Difficulty::Hard => 0.15,
+        };
+        PhysicsConfig {
+            gravity,
+            ..PhysicsConfig::default()
+        }
+    }
+
+    // gap size before the score starts shrinking it
+    fn base_gap(self) -> i32 {
+        match self {
+            Difficulty::Easy => 24,
+            Difficulty::Normal => 20,
+            Difficulty::Hard => 14,
+        }
+    }
+
+    // world-space columns between two obstacles, closer together is harder
+    fn obstacle_spacing(self) -> i32 {
+        match self {
+            Difficulty::Easy => 36,
+            Difficulty::Normal => OBSTACLE_SPACING,
+            Difficulty::Hard => 24,
+        }
+    }
+
+    // crashes a run can take before it is over
+    fn lives(self) -> i32 {
+        match self {
+            Difficulty::Easy => 5,
+            Difficulty::Normal => 3,
+            Difficulty::Hard => 1,
+        }
+    }
+
+    // milliseconds between physics frames, lower scrolls faster
+    fn frame_duration(self) -> f32 {
+        match self {
+            Difficulty::Easy => 90.0,
+            Difficulty::Normal => FRAME_DURATION,
+            Difficulty::Hard => 55.0,
+        }
+    }
+
+    // the next harder difficulty, wrapping around to Easy
+    fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    // the next easier difficulty, wrapping around to Hard
+    fn previous(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Hard,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum GameMode {
+    // the title shown at launch, until a key is pressed or the time is up
+    Splash { seconds_left: f32 },
+    Menu,
+    // 3-2-1 before a run, with the world frozen
+    Countdown,
+    Playing,
+    Paused,
+    // the crash explosion, before the death screen
+    Dying,
+    End,
+    // typing initials for a new top 10 score
+    EnterInitials,
+    // typing in a score code someone shared, to fly the same run
+    EnterCode,
+    // list of the top 10 scores
+    Leaderboard,
+    // every achievement, locked or not
+    Achievements,
+    // volume and difficulty
+    Settings,
+    // a slow practice run through a single obstacle
+    Tutorial,
+    // the autopilot flying a run behind the menu, until any key is pressed
+    Demo,
+    // "Really quit?" over the screen the quit key was pressed on
+    ConfirmQuit,
+    // "Reset all records?" over the settings
+    ConfirmReset,
+}
+
+// the kinds of run that can be started from the menu
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum RunKind {
+    Normal,
+    // today's course, the same for everyone
+    Daily,
+    // two players on one keyboard
+    Race,
+    // a normal run where only the cells near the player can be seen
+    Fog,
+    // one life, faster, smaller gaps and no power-ups, with its own best
+    Hardcore,
+    // practice flying, nothing crashes and nothing is scored
+    Zen,
+    // as many points as possible before the clock runs out,
+    // crashing only costs time, with its own best
+    TimeAttack,
+}
+
+// the lines of the main menu, each with its own hotkey
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuItem {
+    Play,
+    Daily,
+    Race,
+    Fog,
+    Hardcore,
+    Zen,
+    TimeAttack,
+    Tutorial,
+    EnterCode,
+    Leaderboard,
+    Achievements,
+    Settings,
+    Quit,
+}
+
+impl MenuItem {
+    // how an item reads on the menu, without its hotkey
+    fn name(self) -> &'static str {
+        match self {
+            MenuItem::Play => "Play Game",
+            MenuItem::Daily => "Daily Challenge",
+            MenuItem::Race => "Two-player race",
+            MenuItem::Fog => "Fog",
+            MenuItem::Hardcore => "Hardcore",
+            MenuItem::Zen => "Zen practice",
+            MenuItem::TimeAttack => "Time attack",
+            MenuItem::Tutorial => "Tutorial",
+            MenuItem::EnterCode => "Enter score code",
+            MenuItem::Leaderboard => "Leaderboard",
+            MenuItem::Achievements => "Achievements",
+            MenuItem::Settings => "Settings",
+            MenuItem::Quit => "Quit Game",
+        }
+    }
+}
+
+// the main menu top to bottom, a new entry only needs adding here
+// and to the matches in MenuItem::name, menu_key and choose
+const MENU_ITEMS: [MenuItem; 13] = [
+    MenuItem::Play,
+    MenuItem::Daily,
+    MenuItem::Race,
+    MenuItem::Fog,
+    MenuItem::Hardcore,
+    MenuItem::Zen,
+    MenuItem::TimeAttack,
+    MenuItem::Tutorial,
+    MenuItem::EnterCode,
+    MenuItem::Leaderboard,
+    MenuItem::Achievements,
+    MenuItem::Settings,
+    MenuItem::Quit,
+];
+
+// row of the first menu item, the others follow one per row
+const MENU_TOP: i32 = 5;
+
+// how far the player has got through the tutorial
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TutorialStep {
+    // the world waits for the first flap
+    Flap,
+    // flying towards the obstacle
+    Gap,
+    // the obstacle was passed, back to the menu once the time is up
+    Done { seconds_left: f32 },
+}
+
+// cheats for trying out courses and obstacles, only there with --debug
+#[derive(Debug, Default, Clone, Copy)]
+struct Cheats {
+    // nothing can crash the player, toggled with Ctrl+I
+    godmode: bool,
+    // godmode was on at some point of the current run, so it doesn't count
+    used: bool,
+}
+
+// where a state keeps the records, stats, settings and replays
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Storage {
+    // read from and saved to their files in the working directory
+    Files,
+    // in memory only, a headless state never touches a file
+    Memory,
+}
+
+pub struct State {
+    // player
+    player: Player,
+    // the second bird, only flying in a two-player race
+    player2: Player,
+    // normal run, daily challenge or two-player race
+    run: RunKind,
+    // which players of a race haven't crashed yet, player 1 first
+    alive: [bool; 2],
+    // obstacles each player of a race has passed
+    race_scores: [i32; 2],
+    // frame time
+    frame_time: f32,
+    // how far frame_time is into the next physics frame, 0.0..1.0,
+    // the players are drawn that far along their last move
+    blend: f32,
+    // obstacles currently in front of the player, oldest first
+    obstacles: Vec<Obstacle>,
+    // game mode
+    mode: GameMode,
+    // the screen to go back to if quitting is called off
+    quit_from: GameMode,
+    // the fade between two screens, None when there is none
+    transition: Option<Transition>,
+    // playfield size, speed, physics and obstacle layout
+    config: GameConfig,
+    // player score
+    score: i32,
+    // best score ever reached, loaded from HIGH_SCORE_FILE
+    high_score: i32,
+    // initials of whoever set the high score, empty if unknown
+    high_score_name: String,
+    // the run that just ended beat the high score
+    new_high_score: bool,
+    // volume, difficulty, controls and the rest, saved between runs
+    settings: Settings,
+    // where the records and the rest are kept
+    storage: Storage,
+    // player physics for the current run, taken from the config
+    physics: PhysicsConfig,
+    // sound effects and music, silent unless built with the "audio" feature
+    audio: Audio,
+    // all sound switched off with the mute key
+    muted: bool,
+    // frame rate overlay switched on with the fps key
+    show_fps: bool,
+    // recent frame times, for a frame rate overlay that holds still
+    frame_times: FrameTimes,
+    // where the last screenshot went, or why it failed, and the real
+    // seconds left to show that for
+    screenshot_message: Option<(String, f32)>,
+    // None unless the game was started with --debug
+    cheats: Option<Cheats>,
+    // controller input, never pressed unless built with the "gamepad" feature
+    gamepad: Gamepad,
+    // parallax scenery behind the obstacles
+    background: Background,
+    // shared by everything random, so a run only depends on where it starts
+    random: RandomNumberGenerator,
+    // the seed `random` was started from for the current run
+    seed: u64,
+    // every run uses this seed instead of picking a new one, from --seed
+    fixed_seed: Option<u64>,
+    // the inputs of the current run, saved when it ends
+    recording: Replay,
+    // the run being watched with --replay, its inputs are used instead of the player's
+    playback: Option<Replay>,
+    // real seconds the menu has gone without a key press
+    idle_time: f32,
+    // the menu item Up/Down have moved to, Enter picks it
+    menu_index: usize,
+    // day of the daily challenge last played
+    daily_date: Date,
+    // best score of that day's challenge, kept apart from the high score
+    daily_best: i32,
+    // best hardcore score, loaded from HARDCORE_FILE
+    hardcore_best: i32,
+    // best time attack score, loaded from TIME_ATTACK_FILE
+    time_attack_best: i32,
+    // real seconds left on the clock of a time attack run
+    time_left: f32,
+    // score the next boss shows up at
+    next_boss_score: i32,
+    // handcrafted obstacles every run starts with, from --course
+    course: Vec<CourseObstacle>,
+    // the next of them to spawn, random obstacles follow once they run out
+    course_position: usize,
+    // power-ups waiting to be collected
+    power_ups: Vec<PowerUp>,
+    // real seconds of slow-mo left
+    slowmo_timer: f32,
+    // seconds of flipped gravity left, 0.0 when gravity pulls down
+    flip_timer: f32,
+    // seconds of magnet left, coins are only pulled in while above 0.0
+    magnet_timer: f32,
+    // coins waiting to be collected
+    coin_pickups: Vec<Coin>,
+    // coins collected this run, counted apart from the obstacle score
+    coins: i32,
+    // crashes left before the run is over
+    lives: i32,
+    // close passes in a row
+    combo: i32,
+    // points every passed obstacle is worth right now
+    multiplier: i32,
+    // physics frames left flashing the multiplier in the HUD
+    multiplier_flash: i32,
+    // "+1" and the like for points just earned
+    popups: Vec<ScorePopup>,
+    // top scores with initials, best first
+    leaderboard: Vec<(String, i32)>,
+    // unlocked achievements and progress towards the others
+    achievements: Achievements,
+    // the achievement just unlocked and real seconds left announcing it
+    toast: Option<(Achievement, f32)>,
+    // totals across every session, saved after every run
+    stats: Stats,
+    // initials typed so far on the EnterInitials screen
+    initials: String,
+    // code typed so far on the EnterCode screen
+    code_entry: String,
+    // why the last code typed in was turned down, shown under it
+    code_error: Option<String>,
+    // the shared run being flown, its score is the one to beat
+    challenge: Option<ScoreCode>,
+    // real seconds left before the run starts
+    countdown: f32,
+    // velocity bias of the current gust, negative blows upwards, 0.0 is calm
+    wind: f32,
+    // seconds left of the current gust
+    wind_timer: f32,
+    // the player's row on every physics frame of this run
+    trace: Vec<i32>,
+    // trace of the best run so far, raced as a ghost
+    ghost: Vec<i32>,
+    // progress through the tutorial
+    tutorial: TutorialStep,
+    // reused for text that changes every frame, like the score
+    // it is cleared and written into instead of calling format!,
+    // so once it has grown to fit the longest line drawing allocates nothing
+    text: String,
+    // sparks of the crash explosion
+    particles: Vec<Particle>,
+    // real seconds left of the crash explosion
+    death_timer: f32,
+    // cells the screen currently jolts by, dying down to 0.0
+    shake: f32,
+    // picks the jolts, kept apart from `random` so shaking
+    // can't change the course of a seeded run
+    shake_random: RandomNumberGenerator,
+}
+
+// read the saved high score of a scoring and the initials that go with it
+// the file holds "SCORE INITIALS", a missing or corrupt file counts as 0
+fn load_high_score(scoring: ScoringMode) -> (i32, String) {
+    let text = std::fs::read_to_string(scoring.records_file(HIGH_SCORE_FILE)).unwrap_or_default();
+    let mut parts = text.split_whitespace();
+    match parts.next().and_then(|score| score.parse().ok()) {
+        Some(score) => (score, parts.next().unwrap_or_default().to_string()),
+        None => (0, String::new()),
+    }
+}
+
+// a best score kept on its own in `file`, 0 if missing or corrupt
+fn load_best(file: &str, scoring: ScoringMode) -> i32 {
+    std::fs::read_to_string(scoring.records_file(file))
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn save_best(file: &str, score: i32, scoring: ScoringMode) {
+    let file = scoring.records_file(file);
+    if let Err(err) = std::fs::write(&file, score.to_string()) {
+        eprintln!("Could not save best score to {file}: {err}");
+    }
+}
+
+// write the high score back as plain text
+fn save_high_score(high_score: i32, name: &str, scoring: ScoringMode) {
+    let text = format!("{high_score} {name}");
+    let file = scoring.records_file(HIGH_SCORE_FILE);
+    if let Err(err) = std::fs::write(&file, text.trim_end()) {
+        eprintln!("Could not save high score to {file}: {err}");
+    }
+}
+
+// what the player did during one rendered frame,
+// everything a run needs from the window to move on
+// anything missing from an older replay is left as not pressed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct FrameInput {
+    // real milliseconds since the last frame
+    frame_time_ms: f32,
+    flap: bool,
+    // the second racer's flap, only used in a race
+    flap2: bool,
+    // whether each racer's flap key is down, player 1 first
+    held: [bool; 2],
+}
+
+// true on the frame the left mouse button goes down
+// bracket-lib flags left_click on the release as well,
+// so the button has to be held for it to count
+fn clicked(ctx: &BTerm) -> bool {
+    ctx.left_click && INPUT.lock().is_mouse_button_pressed(0)
+}
+
+impl State {
+    // game state constructor to initialize instance
+    // every run gets a new random seed
+    #[must_use]
+    pub fn with_config(config: GameConfig) -> Self {
+        let seed = RandomNumberGenerator::new().next_u64();
+        State {
+            fixed_seed: None,
+            ..State::with_settings(config, Settings::load(), seed)
+        }
+    }
+
+    // every run plays out from the same seed, so it can be replayed
+    // the records, achievements and stats are the saved ones
+    fn with_settings(config: GameConfig, settings: Settings, seed: u64) -> Self {
+        let mut state = State {
+            storage: Storage::Files,
+            achievements: Achievements::load(),
+            stats: Stats::load(),
+            ..State::in_memory(config, settings, seed)
+        };
+        state.load_records();
+        state
+    }
+
+    // a state that starts with no records and saves nothing, for runs played
+    // headless, so how they go only depends on what they are given
+    // nothing is left to unlock, the achievements would save otherwise
+    fn in_memory(config: GameConfig, settings: Settings, seed: u64) -> Self {
+        let screen = config.screen;
+        let mut audio = Audio::new();
+        audio.set_volume(settings.volume_factor());
+        let mut random = RandomNumberGenerator::seeded(seed);
+        State {
+            player: Player::new(START_X, screen.height / 2, &config.physics),
+            // no race until one is started from the menu
+            player2: Player::new(START_X, screen.height / 2, &config.physics),
+            run: RunKind::Normal,
+            alive: [true, true],
+            race_scores: [0, 0],
+            //default frame time
+            frame_time: 0.0,
+            blend: 0.0,
+            // first obstacle at the right edge of the screen
+            obstacles: vec![Obstacle::new(
+                screen.width,
+                0,
+                config.base_gap,
+                screen.height,
+                &mut random,
+            )],
+            // default entering menu mode
+            mode: GameMode::Menu,
+            quit_from: GameMode::Menu,
+            transition: None,
+            config,
+            // default score
+            score: 0,
+            // no score to beat yet
+            high_score: 0,
+            high_score_name: String::new(),
+            new_high_score: false,
+            // saved settings from previous runs
+            settings,
+            storage: Storage::Memory,
+            physics: config.physics,
+            // sounds loaded once, at the saved volume
+            audio,
+            // sound on
+            muted: false,
+            show_fps: false,
+            frame_times: FrameTimes::new(),
+            screenshot_message: None,
+            cheats: None,
+            // controllers are picked up whenever they are plugged in
+            gamepad: Gamepad::new(),
+            // scenery is generated once and reused for every run
+            background: Background::new(screen.width, screen.height, &mut random),
+            // every random choice of the game comes from here
+            random,
+            seed,
+            fixed_seed: Some(seed),
+            recording: Replay::new(seed, settings.difficulty, screen, RunKind::Normal),
+            playback: None,
+            idle_time: 0.0,
+            menu_index: 0,
+            // the daily challenge is picked on the menu
+            daily_date: Date::today(),
+            daily_best: 0,
+            hardcore_best: 0,
+            time_attack_best: 0,
+            time_left: TIME_ATTACK_SECONDS,
+            // nothing to collect yet
+            next_boss_score: BOSS_EVERY,
+            course: Vec::new(),
+            course_position: 0,
+            power_ups: Vec::new(),
+            // normal speed
+            slowmo_timer: 0.0,
+            flip_timer: 0.0,
+            magnet_timer: 0.0,
+            // no coins yet
+            coin_pickups: Vec::new(),
+            coins: 0,
+            // no shared run is picked yet, so this is what difficulty() gives
+            lives: settings.difficulty.lives(),
+            // no combo yet
+            combo: 0,
+            multiplier: 1,
+            multiplier_flash: 0,
+            // no points earned yet
+            popups: Vec::new(),
+            // nobody on the top scores yet
+            leaderboard: Vec::new(),
+            achievements: Achievements::all_unlocked(),
+            toast: None,
+            stats: Stats::default(),
+            initials: String::new(),
+            code_entry: String::new(),
+            code_error: None,
+            challenge: None,
+            // no countdown until a game starts
+            countdown: 0.0,
+            // calm to begin with
+            wind: 0.0,
+            wind_timer: 0.0,
+            // nothing recorded yet, the saved ghost is loaded on restart
+            trace: Vec::new(),
+            ghost: Vec::new(),
+            // the tutorial always starts from the beginning
+            tutorial: TutorialStep::Flap,
+            text: String::new(),
+            // nothing has exploded yet
+            particles: Vec::new(),
+            death_timer: 0.0,
+            // steady until something is hit
+            shake: 0.0,
+            shake_random: RandomNumberGenerator::new(),
+        }
+    }
+
+    // every field about the run starts over here, a new field has to be
+    // added too unless it belongs to the session: the settings, controls,
+    // records, stats, achievements, the course, the menus' and the audio's
+    // own state, and whichever shake_random or fixed_seed it started with
+    fn restart(&mut self) {
+        // start the random numbers over, from a new seed unless it is fixed
+        // the daily challenge always uses today's date,
+        // and a replay the seed it was recorded with
+        if let Some(replay) = &self.playback {
+            self.seed = replay.seed;
+        } else if let Some(challenge) = self.challenge {
+            self.seed = challenge.seed;
+        } else if self.run == RunKind::Daily {
+            self.daily_date = Date::today();
+            if self.storage == Storage::Files {
+                self.daily_best =
+                    load_daily_best(self.daily_date, self.config.screen, self.config.scoring);
+            }
+            self.seed = self.daily_date.seed();
+        } else {
+            self.seed = self.fixed_seed.unwrap_or_else(|| self.random.next_u64());
+        }
+        self.random = RandomNumberGenerator::seeded(self.seed);
+        self.recording = Replay::new(self.seed, self.difficulty(), self.config.screen, self.run);
+        self.recording.course.clone_from(&self.course);
+        self.recording.scoring = self.config.scoring;
+        // a run only counts if godmode stays off all the way through
+        if let Some(cheats) = &mut self.cheats {
+            cheats.used = cheats.godmode;
+        }
+        // construct new player and make initialization
+        // racers start a little apart so both can be seen
+        // the second bird is reset even when it sits the run out,
+        // so nothing of an earlier race is left in it
+        let (height, physics) = (self.config.screen.height, &self.config.physics);
+        if self.run == RunKind::Race {
+            self.player = Player::new(START_X, height / 2 - 3, physics);
+        } else {
+            self.player = Player::new(START_X, height / 2, physics);
+        }
+        self.player2 = Player::new(START_X, height / 2 + 3, physics);
+        self.alive = [true, true];
+        self.race_scores = [0, 0];
+        //clear frame time
+        self.frame_time = 0.0;
+        self.blend = 0.0;
+        //pick up the physics of the config
+        self.physics = self.config.physics;
+        //construct obstacles
+        self.course_position = 0;
+        self.obstacles.clear();
+        let first = self.next_obstacle(self.config.screen.width, 0);
+        self.obstacles.push(first);
+        self.next_boss_score = BOSS_EVERY;
+        //clear power-ups
+        self.power_ups.clear();
+        self.slowmo_timer = 0.0;
+        self.flip_timer = 0.0;
+        self.magnet_timer = 0.0;
+        self.time_left = TIME_ATTACK_SECONDS;
+        //clear coins
+        self.coin_pickups.clear();
+        self.coins = 0;
+        //a full set of lives
+        self.lives = if self.run == RunKind::Hardcore {
+            1
+        } else {
+            self.difficulty().lives()
+        };
+        //clear combo
+        self.combo = 0;
+        self.multiplier = 1;
+        self.multiplier_flash = 0;
+        self.popups.clear();
+        //start counting towards achievements again
+        self.achievements.start_run();
+        self.toast = None;
+        //count down before the run starts
+        self.transition_to(GameMode::Countdown);
+        //calm the wind
+        self.wind = 0.0;
+        self.wind_timer = 0.0;
+        //start a new recording and race the best one so far
+        self.trace.clear();
+        // the ghost flew another course, so it sits out the daily challenge
+        // and it would only get in the way of a race, in memory none is saved
+        self.ghost = if self.run == RunKind::Normal && self.storage == Storage::Files {
+            load_ghost(self.config.scoring)
+        } else {
+            Vec::new()
+        };
+        //back to the first biome
+        self.background.reset();
+        //clear the last explosion
+        self.particles.clear();
+        self.death_timer = 0.0;
+        self.shake = 0.0;
+        //clear score
+        self.score = 0;
+        self.new_high_score = false;
+    }
+
+    // pick the kind of run from the menu, play again repeats it
+    // hardcore has tunables of its own, any other run goes back
+    // to those of its difficulty
+    fn start(&mut self, run: RunKind) {
+        self.run = run;
+        if run == RunKind::Hardcore {
+            self.config.set_hardcore();
+        } else {
+            self.config.set_difficulty(self.difficulty());
+        }
+        self.restart();
+    }
+
+    // a replay and a shared run are flown on the difficulty they were
+    // set on, the daily challenge on DAILY_DIFFICULTY,
+    // anything else on the one picked in the settings
+    fn difficulty(&self) -> Difficulty {
+        if let Some(replay) = &self.playback {
+            return replay.difficulty;
+        }
+        if self.run == RunKind::Daily {
+            return DAILY_DIFFICULTY;
+        }
+        self.challenge
+            .map_or(self.settings.difficulty, |challenge| challenge.difficulty)
+    }
+
+    // fly a recorded run again, the state has to be on the screen it was
+    // recorded on for it to play out the same, the difficulty and scoring
+    // only change the config, the settings keep the player's
+    fn watch(&mut self, replay: Replay) {
+        let run = replay.run;
+        self.course.clone_from(&replay.course);
+        if self.config.scoring != replay.scoring {
+            self.config.scoring = replay.scoring;
+            self.load_records();
+        }
+        self.playback = Some(replay);
+        self.start(run);
+    }
+
+    // the furthest player, who the view follows
+    // in a race a crashed bird stays behind and scrolls off
+    fn leader(&self) -> &Player {
+        if self.run == RunKind::Race && self.player2.x > self.player.x {
+            &self.player2
+        } else {
+            &self.player
+        }
+    }
+
+    // the score of the run as the scoring counts it,
+    // obstacles passed plus the bonus from coins unless picked otherwise
+    fn total_score(&self) -> i32 {
+        match self.config.scoring {
+            ScoringMode::Obstacles => self.score + self.coins * COIN_VALUE,
+            ScoringMode::Distance => self.player.x - START_X,
+            ScoringMode::Coins => self.coins,
+        }
+    }
+
+    // the records kept for the scoring, after it has changed
+    // the daily best and the ghost are read at the start of every run anyway
+    // in memory there is nothing to read, they are those of the last scoring
+    fn load_records(&mut self) {
+        if self.storage == Storage::Memory {
+            return;
+        }
+        let scoring = self.config.scoring;
+        (self.high_score, self.high_score_name) = load_high_score(scoring);
+        self.leaderboard = load_leaderboard(scoring);
+        self.hardcore_best = load_best(HARDCORE_FILE, scoring);
+        self.time_attack_best = load_best(TIME_ATTACK_FILE, scoring);
+    }
+
+    // blow the player up, game_over follows once the explosion is done
+    #[allow(clippy::cast_precision_loss)]
+    fn crash(&mut self) {
+        self.transition_to(GameMode::Dying);
+        self.audio.play_crash();
+        let (x, y) = (self.leader().x, self.leader().y);
+        self.particles = Particle::burst(&mut self.random, x as f32, y as f32, DEATH_PARTICLES);
+        self.shake = self.config.shake_intensity;
+    }
+
+    // finish the current run and keep the high score up to date
+    fn game_over(&mut self) {
+        self.transition_to(GameMode::End);
+        // a replay doesn't set any records, the next run is played again
+        if let Some(replay) = self.playback.take() {
+            if replay.score != self.total_score() {
+                eprintln!(
+                    "warning: the replay ended on {} points, it was recorded ending on {}",
+                    self.total_score(),
+                    replay.score
+                );
+            }
+            // forget what the replay added to the totals
+            self.reload_stats();
+            self.new_high_score = false;
+            // and score the next run the player's way again
+            if self.config.scoring != self.settings.scoring {
+                self.config.scoring = self.settings.scoring;
+                self.load_records();
+            }
+            return;
+        }
+        // nor does a run that was flown through in godmode
+        if self.cheated() {
+            self.reload_stats();
+            self.new_high_score = false;
+            return;
+        }
+        self.recording.score = self.total_score();
+        if self.storage == Storage::Files {
+            self.recording.save();
+        }
+        self.stats.games_played += 1;
+        // the totals only keep the best of the original scoring
+        if self.run != RunKind::Race && self.config.scoring == ScoringMode::Obstacles {
+            self.stats.best_score = i32::max(self.stats.best_score, self.total_score());
+        }
+        self.save_stats();
+        // race scores don't count towards any records
+        if self.run == RunKind::Race {
+            self.new_high_score = false;
+            return;
+        }
+        // the daily challenge only competes with itself
+        if self.run == RunKind::Daily {
+            self.new_high_score = self.total_score() > self.daily_best;
+            if self.new_high_score {
+                self.daily_best = self.total_score();
+                let (date, screen) = (self.daily_date, self.config.screen);
+                if self.storage == Storage::Files {
+                    save_daily_best(date, screen, self.daily_best, self.config.scoring);
+                }
+            }
+            return;
+        }
+        // and so do hardcore and time attack
+        if self.run == RunKind::Hardcore {
+            self.new_high_score = self.total_score() > self.hardcore_best;
+            if self.new_high_score {
+                self.hardcore_best = self.total_score();
+                if self.storage == Storage::Files {
+                    save_best(HARDCORE_FILE, self.hardcore_best, self.config.scoring);
+                }
+            }
+            return;
+        }
+        if self.run == RunKind::TimeAttack {
+            self.new_high_score = self.total_score() > self.time_attack_best;
+            if self.new_high_score {
+                self.time_attack_best = self.total_score();
+                if self.storage == Storage::Files {
+                    save_best(TIME_ATTACK_FILE, self.time_attack_best, self.config.scoring);
+                }
+            }
+            return;
+        }
+        // save straight away so the score isn't lost if the game is closed
+        // while typing, the initials are added once confirmed
+        self.new_high_score = self.total_score() > self.high_score;
+        if self.new_high_score {
+            self.high_score = self.total_score();
+            self.high_score_name.clear();
+            if self.storage == Storage::Files {
+                save_high_score(self.high_score, &self.high_score_name, self.config.scoring);
+                // the best run becomes the ghost to race next time
+                save_ghost(&self.trace, self.config.scoring);
+            }
+        }
+        // a new high score is always good enough for the leaderboard,
+        // ask for initials before going back to the menu
+        if qualifies(&self.leaderboard, self.total_score()) {
+            self.initials.clear();
+            self.transition_to(GameMode::EnterInitials);
+        }
+    }
+
+    // "High Score: 42 (ABC)" into the text buffer, leaving out unknown initials
+    // writing to a String can't fail
+    fn write_high_score(&mut self) {
+        self.text.clear();
+        let _ = write!(self.text, "High Score: {}", self.high_score);
+        if !self.high_score_name.is_empty() {
+            let _ = write!(self.text, " ({})", self.high_score_name);
+        }
+    }
+
+    // print the text buffer without allocating,
+    // bracket-lib's print copies whatever it is given into a new String
+    fn print_text<R: Renderer>(&self, ctx: &mut R, x: i32, y: i32, color: (u8, u8, u8)) {
+        self.print_text_on(ctx, x, y, color, BLACK);
+    }
+
+    // print the text buffer in color over background instead of black
+    fn print_text_on<R: Renderer>(
+        &self,
+        ctx: &mut R,
+        x: i32,
+        y: i32,
+        color: (u8, u8, u8),
+        background: (u8, u8, u8),
+    ) {
+        for (x, glyph) in (x..).zip(self.text.chars()) {
+            draw::set(ctx, x, y, color, background, to_cp437(glyph));
+        }
+    }
+
+    // print the text buffer in the same spot print_centered would
+    fn print_text_centered<R: Renderer>(&self, ctx: &mut R, y: i32, color: (u8, u8, u8)) {
+        self.print_text_centered_on(ctx, y, color, BLACK);
+    }
+
+    // print the text buffer centered, in color over background
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn print_text_centered_on<R: Renderer>(
+        &self,
+        ctx: &mut R,
+        y: i32,
+        color: (u8, u8, u8),
+        background: (u8, u8, u8),
+    ) {
+        let length = self.text.chars().count() as i32;
+        let x = self.config.screen.width / 2 - length / 2;
+        self.print_text_on(ctx, x, y, color, background);
+    }
+
+    // print a line that never changes centered, through the text buffer
+    // so it isn't copied into a new String every frame
+    fn print_line_centered<R: Renderer>(&mut self, ctx: &mut R, y: i32, line: &str) {
+        self.text.clear();
+        self.text.push_str(line);
+        self.print_text_centered(ctx, y, WHITE);
+    }
+
+    // draw the menu, the selected item highlighted
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn render_main_menu<R: Renderer>(&mut self, ctx: &mut R) {
+        // clear game window
+        ctx.cls();
+        // print line(x coordinate) center
+        self.print_line_centered(ctx, 3, "Welcome to Flappy Dragon");
+        self.write_high_score();
+        self.print_text_centered(ctx, 4, WHITE);
+        for (index, item) in MENU_ITEMS.iter().copied().enumerate() {
+            let y = MENU_TOP + index as i32;
+            let selected = index == self.menu_index;
+            self.text.clear();
+            if selected {
+                self.text.push_str("> ");
+            }
+            let _ = write!(self.text, "({:?}) {}", self.menu_key(item), item.name());
+            if selected {
+                self.text.push_str(" <");
+            }
+            if selected && self.settings.accessibility {
+                // the whole line lit up is easier to find than the arrows
+                self.print_text_centered_on(ctx, y, BLACK, WHITE);
+            } else if selected {
+                self.print_text_centered(ctx, y, YELLOW);
+            } else {
+                self.print_text_centered(ctx, y, WHITE);
+            }
+        }
+        let bottom = MENU_TOP + MENU_ITEMS.len() as i32;
+        self.text.clear();
+        let _ = write!(
+            self.text,
+            "(1/2/3) Difficulty: {:?}",
+            self.settings.difficulty
+        );
+        self.print_text_centered(ctx, bottom + 1, WHITE);
+    }
+
+    // how an item reads on the menu, with its hotkey
+    fn menu_label(&self, item: MenuItem) -> String {
+        format!("({:?}) {}", self.menu_key(item), item.name())
+    }
+
+    // the letter that picks an item straight away
+    fn menu_key(&self, item: MenuItem) -> VirtualKeyCode {
+        match item {
+            MenuItem::Play => self.settings.keys.play,
+            MenuItem::Daily => VirtualKeyCode::D,
+            MenuItem::Race => VirtualKeyCode::R,
+            MenuItem::Fog => VirtualKeyCode::F,
+            MenuItem::Hardcore => VirtualKeyCode::H,
+            MenuItem::Zen => VirtualKeyCode::Z,
+            MenuItem::TimeAttack => VirtualKeyCode::K,
+            MenuItem::Tutorial => VirtualKeyCode::T,
+            MenuItem::EnterCode => VirtualKeyCode::E,
+            MenuItem::Leaderboard => VirtualKeyCode::L,
+            MenuItem::Achievements => VirtualKeyCode::A,
+            MenuItem::Settings => VirtualKeyCode::S,
+            MenuItem::Quit => self.settings.keys.quit,
+        }
+    }
+
+    fn choose(&mut self, item: MenuItem) {
+        // only a code that was just typed in starts a shared run
+        self.challenge = None;
+        match item {
+            MenuItem::Play => self.fade(|state| state.start(RunKind::Normal)),
+            // today's course, the same for everyone
+            MenuItem::Daily => self.fade(|state| state.start(RunKind::Daily)),
+            // two birds on one keyboard
+            MenuItem::Race => self.fade(|state| state.start(RunKind::Race)),
+            // obstacles only show up once they are close
+            MenuItem::Fog => self.fade(|state| state.start(RunKind::Fog)),
+            // one hit and it is over
+            MenuItem::Hardcore => self.fade(|state| state.start(RunKind::Hardcore)),
+            // fly for as long as you like
+            MenuItem::Zen => self.fade(|state| state.start(RunKind::Zen)),
+            // beat the clock
+            MenuItem::TimeAttack => self.fade(|state| state.start(RunKind::TimeAttack)),
+            // learn how to play
+            MenuItem::Tutorial => self.fade(State::start_tutorial),
+            // fly a run someone shared
+            MenuItem::EnterCode => {
+                self.code_entry.clear();
+                self.code_error = None;
+                self.transition_to(GameMode::EnterCode);
+            }
+            // show the top scores
+            MenuItem::Leaderboard => self.transition_to(GameMode::Leaderboard),
+            // see what has been unlocked
+            MenuItem::Achievements => self.transition_to(GameMode::Achievements),
+            // change volume and difficulty
+            MenuItem::Settings => self.transition_to(GameMode::Settings),
+            // quit game, once the player confirms
+            MenuItem::Quit => self.transition_to(GameMode::ConfirmQuit),
+        }
+    }
+
+    fn main_menu(&mut self, ctx: &mut BTerm) {
+        self.render_main_menu(ctx);
+
+        if let Some(key) = ctx.key {
+            // handle key incident
+            match key {
+                // pick the difficulty
+                VirtualKeyCode::Key1 => self.set_difficulty(Difficulty::Easy),
+                VirtualKeyCode::Key2 => self.set_difficulty(Difficulty::Normal),
+                VirtualKeyCode::Key3 => self.set_difficulty(Difficulty::Hard),
+
+                // move the highlight, wrapping around at either end
+                VirtualKeyCode::Up => {
+                    self.menu_index = (self.menu_index + MENU_ITEMS.len() - 1) % MENU_ITEMS.len();
+                }
+                VirtualKeyCode::Down => self.menu_index = (self.menu_index + 1) % MENU_ITEMS.len(),
+
+                // pick the highlighted item
+                VirtualKeyCode::Return => self.choose(MENU_ITEMS[self.menu_index]),
+
+                // the hotkeys still work whatever is highlighted,
+                // other keys do nothing
+                key => {
+                    if let Some(item) = MENU_ITEMS
+                        .iter()
+                        .copied()
+                        .find(|&item| self.menu_key(item) == key)
+                    {
+                        self.choose(item);
+                    }
+                }
+            }
+        }
+
+        // clicking an item picks it like its hotkey
+        if clicked(ctx) {
+            let (_, mouse_y) = ctx.mouse_pos();
+            let clicked_item = usize::try_from(mouse_y - MENU_TOP)
+                .ok()
+                .and_then(|index| MENU_ITEMS.get(index).copied())
+                .filter(|&item| {
+                    let label = self.menu_label(item);
+                    self.is_over_centered(ctx, mouse_y, &label)
+                });
+            if let Some(item) = clicked_item {
+                self.choose(item);
+            }
+        }
+
+        // left alone for a while, the menu shows off a run
+        self.idle_time += ctx.frame_time_ms / 1000.0;
+        if ctx.key.is_some() || clicked(ctx) {
+            self.idle_time = 0.0;
+        } else if self.idle_time >= DEMO_IDLE_SECONDS && matches!(self.mode, GameMode::Menu) {
+            self.start_demo();
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn splash(&mut self, ctx: &mut BTerm, seconds_left: f32) {
+        ctx.cls();
+        let theme = self.theme();
+        let middle = self.config.screen.height / 2;
+        // the dragon bobs up and down a row as if it were flapping
+        let bob = ((SPLASH_SECONDS - seconds_left) * SPLASH_BOB_SPEED)
+            .sin()
+            .round() as i32;
+        for (y, line) in (middle - 6 + bob..).zip(SPLASH_ART.iter()) {
+            ctx.print_color_centered(y, theme.player, BLACK, line);
+        }
+        ctx.print_color_centered(middle, theme.text, BLACK, "F L A P P Y   D R A G O N");
+        ctx.print_color_centered(middle + 2, self.hint_color(), BLACK, "press any key");
+
+        let seconds_left = seconds_left - ctx.frame_time_ms / 1000.0;
+        self.transition_to(GameMode::Splash { seconds_left });
+        if ctx.key.is_some() || clicked(ctx) || seconds_left <= 0.0 {
+            self.fade(|state| state.transition_to(GameMode::Menu));
+        }
+    }
+
+    // a quiet normal run, flown by should_flap
+    fn start_demo(&mut self) {
+        self.start(RunKind::Normal);
+        self.transition_to(GameMode::Demo);
+    }
+
+    // the autopilot plays until any key is pressed,
+    // a crash just starts another run
+    fn demo(&mut self, ctx: &mut BTerm) {
+        if ctx.key.is_some() || clicked(ctx) {
+            self.transition_to(GameMode::Menu);
+            return;
+        }
+        self.update(FrameInput {
+            frame_time_ms: ctx.frame_time_ms,
+            flap: should_flap(&self.player, self.obstacle_ahead()),
+            ..FrameInput::default()
+        });
+        if !matches!(self.mode, GameMode::Demo) {
+            self.start_demo();
+        }
+        self.render_playfield(ctx);
+        let theme = self.theme();
+        ctx.print_color_centered(
+            self.config.screen.height - 2,
+            theme.text,
+            BLACK,
+            "DEMO - press any key",
+        );
+    }
+
+    // the first obstacle the player hasn't flown past yet
+    fn obstacle_ahead(&self) -> Option<&Obstacle> {
+        self.obstacles
+            .iter()
+            .find(|obstacle| obstacle.right_x() >= self.player.x)
+    }
+
+    // true if the mouse is over text drawn with print_centered on row y
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn is_over_centered(&self, ctx: &BTerm, y: i32, text: &str) -> bool {
+        let (mouse_x, mouse_y) = ctx.mouse_pos();
+        let length = text.len() as i32;
+        // same position print_centered uses
+        let start = self.config.screen.width / 2 - length / 2;
+        mouse_y == y && mouse_x >= start && mouse_x < start + length
+    }
+
+    // draw the results of the run that just ended
+    fn render_dead<R: Renderer>(&mut self, ctx: &mut R) {
+        //clear window text
+        ctx.cls();
+        // print center text on vertical y position
+        self.print_line_centered(ctx, 5, "You are dead!");
+        if self.run == RunKind::Race {
+            self.text.clear();
+            let _ = write!(
+                self.text,
+                "Player 1: {}  Player 2: {}",
+                self.race_scores[0], self.race_scores[1]
+            );
+            self.print_text_centered(ctx, 6, WHITE);
+            let [score1, score2] = self.race_scores;
+            let result = match score1.cmp(&score2) {
+                std::cmp::Ordering::Greater => "Player 1 wins!",
+                std::cmp::Ordering::Less => "Player 2 wins!",
+                std::cmp::Ordering::Equal => "It's a draw!",
+            };
+            self.print_line_centered(ctx, 7, result);
+        } else {
+            self.text.clear();
+            let _ = write!(self.text, "You earned {} points", self.total_score());
+            self.print_text_centered(ctx, 6, WHITE);
+            if self.run == RunKind::Daily {
+                self.text.clear();
+                let _ = write!(
+                    self.text,
+                    "Daily Challenge {}  Best: {}",
+                    self.daily_date, self.daily_best
+                );
+            } else if self.run == RunKind::Hardcore {
+                self.text.clear();
+                let _ = write!(self.text, "Hardcore Best: {}", self.hardcore_best);
+            } else if self.run == RunKind::TimeAttack {
+                self.text.clear();
+                let _ = write!(self.text, "Time Attack Best: {}", self.time_attack_best);
+            } else {
+                self.write_high_score();
+            }
+            self.print_text_centered(ctx, 7, WHITE);
+        }
+        self.text.clear();
+        let _ = write!(self.text, "({:?}) Play Again", self.settings.keys.play);
+        self.print_text_centered(ctx, 8, WHITE);
+        self.text.clear();
+        let _ = write!(self.text, "({:?}) Quit Game", self.settings.keys.quit);
+        self.print_text_centered(ctx, 9, WHITE);
+        // share it with --seed to let someone else fly the same run
+        self.text.clear();
+        let _ = write!(self.text, "Seed: {}", self.seed);
+        self.print_text_centered(ctx, 11, self.hint_color());
+        // the whole run in one line, for Enter score code on the menu
+        // a course from --course or a cheated run can't be shared
+        if self.run == RunKind::Normal && self.course.is_empty() && !self.cheated() {
+            let code = ScoreCode {
+                seed: self.seed,
+                score: self.total_score(),
+                difficulty: self.difficulty(),
+                scoring: self.config.scoring,
+                screen: self.config.screen,
+            };
+            self.text.clear();
+            let _ = write!(self.text, "Score code: {code}");
+            self.print_text_centered(ctx, 12, self.hint_color());
+        }
+        if let Some(challenge) = self.challenge {
+            self.text.clear();
+            if self.total_score() > challenge.score {
+                let _ = write!(self.text, "You beat the shared {} points!", challenge.score);
+            } else {
+                let _ = write!(
+                    self.text,
+                    "The shared {} points still stand",
+                    challenge.score
+                );
+            }
+            self.print_text_centered(ctx, 14, WHITE);
+        }
+    }
+
+    fn dead(&mut self, ctx: &mut BTerm) {
+        self.render_dead(ctx);
+
+        // deal with key incident
+        // if let and match work in the same way
+        if let Some(key) = ctx.key {
+            match key {
+                // restart game
+                key if key == self.settings.keys.play => self.fade(State::restart),
+                // quit game, once the player confirms
+                key if key == self.settings.keys.quit => self.transition_to(GameMode::ConfirmQuit),
+                // do nothing
+                _ => {}
+            }
+        }
+    }
+
+    // every change of screen goes through here, so whatever has to happen
+    // on the way into or out of a screen happens however it is reached
+    // a screen that only updates its own state, like the splash's clock,
+    // comes through here too and nothing extra happens
+    fn transition_to(&mut self, new: GameMode) {
+        let old = std::mem::replace(&mut self.mode, new);
+        // the demo was quiet and its runs don't count,
+        // it only ever goes back to the menu, its crashes just start it over
+        if matches!((old, new), (GameMode::Demo, GameMode::Menu)) {
+            self.audio.set_muted(self.muted);
+            // forget what the demo added to the totals
+            self.reload_stats();
+        }
+        match new {
+            GameMode::Menu => self.idle_time = 0.0,
+            GameMode::Countdown => self.countdown = COUNTDOWN_SECONDS,
+            GameMode::Dying => self.death_timer = DEATH_SECONDS,
+            GameMode::Demo => self.audio.set_muted(true),
+            // N goes back to whatever was on screen
+            GameMode::ConfirmQuit => self.quit_from = old,
+            _ => {}
+        }
+    }
+
+    // a stray press of the quit key shouldn't end the game,
+    // only Y quits and N or Escape goes back where it was
+    fn confirm_quit(&mut self, ctx: &mut BTerm) {
+        // keep showing the screen it was asked from underneath
+        if matches!(self.quit_from, GameMode::End) {
+            self.render_dead(ctx);
+        } else {
+            self.render_main_menu(ctx);
+        }
+        let theme = self.theme();
+        ctx.print_color_centered(
+            self.config.screen.height - 1,
+            theme.text,
+            BLACK,
+            "Really quit? (Y/N)",
+        );
+
+        match ctx.key {
+            Some(VirtualKeyCode::Y) => self.quit(ctx),
+            Some(VirtualKeyCode::N | VirtualKeyCode::Escape) => self.transition_to(self.quit_from),
+            _ => {}
+        }
+    }
+
+    // the one way out of the game, from Y above or closing the window
+    // a run that is still going is finished first so its score and totals
+    // are kept, everything else was already saved the moment it changed
+    fn quit(&mut self, ctx: &mut BTerm) {
+        if ctx.quitting {
+            return;
+        }
+        let in_run = matches!(
+            self.mode,
+            GameMode::Countdown | GameMode::Playing | GameMode::Paused | GameMode::Dying
+        );
+        // zen has no score to keep, only the totals
+        if in_run && self.run == RunKind::Zen {
+            self.save_stats();
+        } else if in_run {
+            self.game_over();
+        }
+        ctx.quitting = true;
+    }
+
+    fn enter_initials(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        ctx.print_centered(5, "You are dead!");
+        self.text.clear();
+        let _ = write!(self.text, "You earned {} points", self.total_score());
+        self.print_text_centered(ctx, 6, WHITE);
+        if self.new_high_score {
+            ctx.print_centered(8, "New high score! Type your initials:");
+        } else {
+            ctx.print_centered(8, "New top 10 score! Type your initials:");
+        }
+        self.text.clear();
+        let _ = write!(self.text, "{:_<3}", self.initials);
+        self.print_text_centered(ctx, 10, WHITE);
+        ctx.print_centered(12, "(Enter) Confirm  (Backspace) Delete");
+
+        if let Some(key) = ctx.key {
+            match key {
+                // save the entry and go back to the menu
+                VirtualKeyCode::Return if !self.initials.is_empty() => {
+                    let name = std::mem::take(&mut self.initials);
+                    let score = self.total_score();
+                    if self.new_high_score {
+                        self.high_score_name.clone_from(&name);
+                    }
+                    insert(&mut self.leaderboard, name, score);
+                    if self.storage == Storage::Files {
+                        let scoring = self.config.scoring;
+                        if self.new_high_score {
+                            save_high_score(self.high_score, &self.high_score_name, scoring);
+                        }
+                        save_leaderboard(&self.leaderboard, scoring);
+                    }
+                    self.transition_to(GameMode::Menu);
+                }
+                // take back the last letter
+                VirtualKeyCode::Back => {
+                    self.initials.pop();
+                }
+                // add a letter while there is room, anything else is ignored
+                key => {
+                    if let Some(letter) = letter_for_key(key) {
+                        if self.initials.len() < INITIALS_LENGTH {
+                            self.initials.push(letter);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn enter_code(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        ctx.print_centered(5, "Enter a score code");
+        // a long code on a narrow screen only shows its end
+        let width = usize::try_from(self.config.screen.width - 2)
+            .unwrap_or_default()
+            .min(MAX_CODE_LENGTH);
+        let shown = &self.code_entry[self.code_entry.len().saturating_sub(width)..];
+        self.text.clear();
+        let _ = write!(self.text, "{shown:_<width$}");
+        self.print_text_centered(ctx, 8, WHITE);
+        if let Some(error) = &self.code_error {
+            ctx.print_color_centered(10, RED, BLACK, error);
+        }
+        ctx.print_centered(12, "(Enter) Play  (Backspace) Delete  (Esc) Back");
+
+        if let Some(key) = ctx.key {
+            match key {
+                VirtualKeyCode::Return => match ScoreCode::decode(&self.code_entry) {
+                    // the score only means something counted the same way
+                    Ok(code) if code.scoring != self.config.scoring => {
+                        self.code_error = Some(format!(
+                            "that run was scored by {:?}, change it in the settings",
+                            code.scoring
+                        ));
+                    }
+                    // and the course only comes out the same on the same screen
+                    Ok(code) if code.screen != self.config.screen => {
+                        self.code_error = Some(format!(
+                            "that run was flown on {}, start with --width {} --height {}",
+                            code.screen, code.screen.width, code.screen.height
+                        ));
+                    }
+                    Ok(code) => {
+                        self.challenge = Some(code);
+                        self.fade(|state| state.start(RunKind::Normal));
+                    }
+                    Err(err) => self.code_error = Some(err),
+                },
+                // take back the last character
+                VirtualKeyCode::Back => {
+                    self.code_entry.pop();
+                }
+                VirtualKeyCode::Escape => self.transition_to(GameMode::Menu),
+                // add a character while there is room, anything else is ignored
+                key => {
+                    if let Some(c) = char_for_key(key) {
+                        if self.code_entry.len() < MAX_CODE_LENGTH {
+                            self.code_entry.push(c);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn leaderboard(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        ctx.print_centered(5, "Leaderboard");
+        if self.leaderboard.is_empty() {
+            ctx.print_centered(8, "No scores yet");
+        }
+        for (rank, (name, score)) in self.leaderboard.iter().enumerate() {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            let y = 8 + rank as i32;
+            self.text.clear();
+            let _ = write!(self.text, "{:>2}. {:<3} {:>6}", rank + 1, name, score);
+            self.print_text_centered(ctx, y, WHITE);
+        }
+        ctx.print_centered(20, "(B) Back");
+
+        if let Some(VirtualKeyCode::B) = ctx.key {
+            self.transition_to(GameMode::Menu);
+        }
+    }
+
+    fn achievements(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        ctx.print_centered(5, "Achievements");
+        for (y, achievement) in (8..).zip(Achievement::ALL) {
+            let mark = if self.achievements.is_unlocked(achievement) {
+                'x'
+            } else {
+                ' '
+            };
+            self.text.clear();
+            let _ = write!(
+                self.text,
+                "[{mark}] {}: {}",
+                achievement.name(),
+                achievement.description()
+            );
+            self.print_text_centered(ctx, y, WHITE);
+        }
+        ctx.print_centered(20, "(B) Back");
+
+        if let Some(VirtualKeyCode::B) = ctx.key {
+            self.transition_to(GameMode::Menu);
+        }
+    }
+
+    fn render_settings(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        ctx.print_centered(5, "Settings");
+        let settings = self.settings;
+        self.text.clear();
+        let _ = write!(self.text, "Volume: {}%", settings.volume);
+        self.print_text_centered(ctx, 8, WHITE);
+        self.text.clear();
+        let _ = write!(self.text, "Difficulty: {:?}", settings.difficulty);
+        self.print_text_centered(ctx, 9, WHITE);
+        self.text.clear();
+        let _ = write!(self.text, "Theme: {:?}", settings.theme);
+        self.print_text_centered(ctx, 10, WHITE);
+        self.text.clear();
+        let _ = write!(self.text, "Scoring: {:?}", settings.scoring);
+        self.print_text_centered(ctx, 11, WHITE);
+        let accessibility = if settings.accessibility {
+            "On, high contrast"
+        } else {
+            "Off"
+        };
+        self.text.clear();
+        let _ = write!(self.text, "Accessibility: {accessibility}");
+        self.print_text_centered(ctx, 12, WHITE);
+        ctx.print_centered(13, "(Left/Right) Volume  (Up/Down) Difficulty");
+        ctx.print_centered(14, "(T) Theme  (C) Scoring  (A) Accessibility");
+        ctx.print_centered(15, "(R) Reset records");
+        ctx.print_centered(20, "(B) Back");
+    }
+
+    fn settings(&mut self, ctx: &mut BTerm) {
+        self.render_settings(ctx);
+
+        if let Some(key) = ctx.key {
+            match key {
+                VirtualKeyCode::Left => {
+                    self.settings.volume_down();
+                    self.apply_settings();
+                }
+                VirtualKeyCode::Right => {
+                    self.settings.volume_up();
+                    self.apply_settings();
+                }
+                VirtualKeyCode::Up => self.set_difficulty(self.settings.difficulty.next()),
+                VirtualKeyCode::Down => self.set_difficulty(self.settings.difficulty.previous()),
+                VirtualKeyCode::T => {
+                    self.settings.theme = self.settings.theme.next();
+                    self.apply_settings();
+                }
+                // every scoring keeps records of its own
+                VirtualKeyCode::C => {
+                    self.settings.scoring = self.settings.scoring.next();
+                    self.config.scoring = self.settings.scoring;
+                    self.load_records();
+                    self.apply_settings();
+                }
+                // high contrast takes over from the theme while it is on
+                VirtualKeyCode::A => {
+                    self.settings.accessibility = !self.settings.accessibility;
+                    self.apply_settings();
+                }
+                VirtualKeyCode::R => self.transition_to(GameMode::ConfirmReset),
+                VirtualKeyCode::B => self.transition_to(GameMode::Menu),
+                _ => {}
+            }
+        }
+    }
+
+    // the records are gone for good once reset, so it takes a Y to do it
+    fn confirm_reset(&mut self, ctx: &mut BTerm) {
+        self.render_settings(ctx);
+        let theme = self.theme();
+        self.text.clear();
+        let _ = write!(
+            self.text,
+            "Reset all {:?} records? (Y/N)",
+            self.config.scoring
+        );
+        self.print_text_centered(ctx, self.config.screen.height - 1, theme.text);
+
+        match ctx.key {
+            Some(VirtualKeyCode::Y) => {
+                self.reset_records();
+                self.transition_to(GameMode::Settings);
+            }
+            Some(VirtualKeyCode::N | VirtualKeyCode::Escape) => {
+                self.transition_to(GameMode::Settings);
+            }
+            _ => {}
+        }
+    }
+
+    // wipe the high score, leaderboard, ghost and best scores of the
+    // current scoring, on disk too, the totals in the stats are kept
+    fn reset_records(&mut self) {
+        self.high_score = 0;
+        self.high_score_name.clear();
+        self.leaderboard.clear();
+        self.hardcore_best = 0;
+        self.time_attack_best = 0;
+        self.daily_best = 0;
+        if self.storage == Storage::Memory {
+            return;
+        }
+        let scoring = self.config.scoring;
+        save_high_score(0, "", scoring);
+        save_leaderboard(&self.leaderboard, scoring);
+        save_ghost(&[], scoring);
+        save_best(HARDCORE_FILE, 0, scoring);
+        save_best(TIME_ATTACK_FILE, 0, scoring);
+        save_daily_best(self.daily_date, self.config.screen, 0, scoring);
+    }
+
+    fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.settings.difficulty = difficulty;
+        self.config.set_difficulty(difficulty);
+        self.apply_settings();
+    }
+
+    // the key a controller button means on the current screen
+    // A flaps or starts a game, Start pauses, B quits or goes back
+    fn key_for_button(&self, button: Button) -> Option<VirtualKeyCode> {
+        match (button, &self.mode) {
+            (Button::South, GameMode::Playing) => Some(self.settings.keys.flap),
+            // on the menu A picks the highlighted item
+            (Button::South | Button::Start, GameMode::Menu) => Some(VirtualKeyCode::Return),
+            // any button stops the demo or skips the title
+            (Button::South | Button::Start, GameMode::End)
+            | (_, GameMode::Demo | GameMode::Splash { .. }) => Some(self.settings.keys.play),
+            (Button::Start, GameMode::Playing | GameMode::Paused) => Some(self.settings.keys.pause),
+            (Button::East, GameMode::Menu | GameMode::End | GameMode::Paused) => {
+                Some(self.settings.keys.quit)
+            }
+            (Button::East, GameMode::Playing) if self.run == RunKind::Zen => {
+                Some(VirtualKeyCode::B)
+            }
+            (Button::East, GameMode::Leaderboard | GameMode::Achievements | GameMode::Settings) => {
+                Some(VirtualKeyCode::B)
+            }
+            // A confirms quitting or a reset, B calls it off
+            (Button::South, GameMode::ConfirmQuit | GameMode::ConfirmReset) => {
+                Some(VirtualKeyCode::Y)
+            }
+            (Button::East, GameMode::ConfirmQuit | GameMode::ConfirmReset) => {
+                Some(VirtualKeyCode::N)
+            }
+            (Button::East, GameMode::EnterCode) => Some(VirtualKeyCode::Escape),
+            _ => None,
+        }
+    }
+
+    // pass changed settings on to the audio and keep them for next time
+    fn apply_settings(&mut self) {
+        self.audio.set_volume(self.settings.volume_factor());
+        if self.storage == Storage::Files {
+            self.settings.save();
+        }
+    }
+
+    // keep the totals for next time, unless the state is only in memory
+    fn save_stats(&self) {
+        if self.storage == Storage::Files {
+            self.stats.save();
+        }
+    }
+
+    // back to the saved totals, forgetting a run that doesn't count
+    // in memory nothing was saved to go back to, so they are kept
+    fn reload_stats(&mut self) {
+        if self.storage == Storage::Files {
+            self.stats = Stats::load();
+        }
+    }
+
+    fn play(&mut self, ctx: &mut BTerm) {
+        // press the pause key to pause
+        // the world is frozen as it is and frame_time is left untouched
+        if ctx.key == Some(self.settings.keys.pause) {
+            self.transition_to(GameMode::Paused);
+            self.render_playfield(ctx);
+            return;
+        }
+
+        // zen never ends by itself, B goes back to the menu
+        // nothing calls game_over, so the totals are saved here instead
+        if self.run == RunKind::Zen && ctx.key == Some(VirtualKeyCode::B) {
+            self.save_stats();
+            self.fade(|state| state.transition_to(GameMode::Menu));
+        }
+
+        // press the flap key or click to flap,
+        // a replay plays out the recorded frames and then just falls
+        let input = match &mut self.playback {
+            Some(replay) => replay.next_frame().unwrap_or(FrameInput {
+                frame_time_ms: ctx.frame_time_ms,
+                ..FrameInput::default()
+            }),
+            None => FrameInput {
+                frame_time_ms: ctx.frame_time_ms,
+                flap: ctx.key == Some(self.settings.keys.flap) || clicked(ctx),
+                flap2: ctx.key == Some(self.settings.keys.flap2),
+                held: self.flaps_held(),
+            },
+        };
+        if self.playback.is_none() {
+            self.recording.record(input);
+        }
+        self.update(input);
+        let (dx, dy) = self.shake_offset(input.frame_time_ms / 1000.0);
+        self.render_playfield(&mut Offset::new(ctx, dx, dy));
+    }
+
+    // whether each racer's flap key is down right now, player 1 first
+    // the mouse button counts for player 1, like a click flaps
+    fn flaps_held(&self) -> [bool; 2] {
+        let input = INPUT.lock();
+        [
+            input.is_key_pressed(self.settings.keys.flap) || input.is_mouse_button_pressed(0),
+            input.is_key_pressed(self.settings.keys.flap2),
+        ]
+    }
+
+    // how far to jolt the screen this frame, letting the shake die down by dt
+    #[allow(clippy::cast_possible_truncation)]
+    fn shake_offset(&mut self, dt: f32) -> (i32, i32) {
+        self.shake = f32::max(0.0, self.shake - SHAKE_DECAY * dt);
+        let amount = self.shake.round() as i32;
+        if amount == 0 {
+            return (0, 0);
+        }
+        (
+            self.shake_random.range(-amount, amount + 1),
+            self.shake_random.range(-amount, amount + 1),
+        )
+    }
+
+    // collect the power-ups and coins the player flew into
+    fn pick_up(&mut self) {
+        if let Some(index) = self
+            .power_ups
+            .iter()
+            .position(|power_up| power_up.touches(&self.player))
+        {
+            let power_up = self.power_ups.remove(index);
+            self.collect(power_up.kind);
+        }
+
+        while let Some(index) = self
+            .coin_pickups
+            .iter()
+            .position(|coin| coin.touches(&self.player))
+        {
+            self.coin_pickups.remove(index);
+            self.coins += 1;
+            self.popups.push(ScorePopup::new(
+                POPUP_X,
+                self.player.y,
+                format!("+{COIN_VALUE}"),
+            ));
+        }
+    }
+
+    // advance a run by one rendered frame: physics, scoring, spawning,
+    // pickups and collisions, without drawing anything
+    fn update(&mut self, input: FrameInput) {
+        // time attack is over once the clock runs out,
+        // the world stops while the screen fades out
+        if self.run == RunKind::TimeAttack {
+            self.time_left = f32::max(0.0, self.time_left - input.frame_time_ms / 1000.0);
+            if self.time_left <= 0.0 {
+                self.fade(State::game_over);
+                return;
+            }
+        }
+
+        // accumulate real time and step the physics in fixed increments,
+        // carrying whatever is left over into the next frame
+        self.frame_time += input.frame_time_ms;
+        self.player.flap_held = input.held[0];
+        self.player2.flap_held = input.held[1];
+        self.stats.play_seconds += f64::from(input.frame_time_ms) / 1000.0;
+        // the world speeds up as the score goes up
+        let step = self.config.frame_duration_for_score(self.course_score());
+
+        // slow-mo stretches the real time between physics frames,
+        // each frame still simulates the normal step so the world runs
+        // slower as a whole and scoring and spawning stay in step with it
+        let mut frame_length = step;
+        if self.slowmo_timer > 0.0 {
+            self.slowmo_timer = f32::max(0.0, self.slowmo_timer - input.frame_time_ms / 1000.0);
+            frame_length *= SLOWMO_FACTOR;
+        }
+        self.flip_timer = f32::max(0.0, self.flip_timer - input.frame_time_ms / 1000.0);
+        self.player.gravity_dir = if self.flip_timer > 0.0 { -1.0 } else { 1.0 };
+        self.magnet_timer = f32::max(0.0, self.magnet_timer - input.frame_time_ms / 1000.0);
+        #[allow(clippy::cast_precision_loss)]
+        let max_time = frame_length * self.config.max_steps_per_frame as f32;
+        self.frame_time = self.frame_time.min(max_time);
+
+        // where the racers were before this frame, to score what they pass
+        let start_x = [self.player.x, self.player2.x];
+        while self.frame_time >= frame_length {
+            self.frame_time -= frame_length;
+            self.physics_step(step);
+            // the world stops where the run ended
+            if matches!(self.mode, GameMode::Dying) {
+                break;
+            }
+        }
+        self.blend = self.frame_time / frame_length;
+        // a press that finds no flap left is kept for the next physics frames
+        let dt = input.frame_time_ms / 1000.0;
+        self.player.expire_flap_buffer(dt);
+        self.player2.expire_flap_buffer(dt);
+        if self.alive[0] && input.flap && self.player.press_flap(&self.physics) {
+            self.flapped();
+        }
+        if self.run == RunKind::Race
+            && self.alive[1]
+            && input.flap2
+            && self.player2.press_flap(&self.physics)
+        {
+            self.flapped();
+        }
+
+        // the scenery changes as the score climbs
+        self.background.update(self.course_score(), dt);
+
+        // popups live in real time, slow-mo doesn't keep them up longer
+        update_all(&mut self.popups, dt);
+        self.popups.retain(ScorePopup::is_alive);
+
+        if self.run == RunKind::Race {
+            self.score_race(start_x);
+        } else if self.run != RunKind::Zen {
+            self.score_passes();
+            // a replay or the demo doesn't earn anything
+            if self.playback.is_none() && !matches!(self.mode, GameMode::Demo) && !self.cheated() {
+                self.unlock_achievements(dt);
+            }
+        }
+
+        self.spawn();
+
+        // forget obstacles that scrolled off the left edge (screen_x < 0)
+        let player_x = self.leader().x;
+        self.obstacles
+            .retain(|obstacle| obstacle.right_x() >= player_x);
+        self.power_ups.retain(|power_up| power_up.x >= player_x);
+        self.coin_pickups.retain(|coin| coin.x >= player_x);
+    }
+
+    // move everything on by one physics frame of `step` milliseconds
+    // pickups and crashes are checked after every one of them, a frame of
+    // several steps would otherwise fly straight through whatever the
+    // players were in on the way, seeing only where they ended up
+    fn physics_step(&mut self, step: f32) {
+        // where the racers were before this step, to see what they hit
+        let start_x = [self.player.x, self.player2.x];
+        self.blow_wind(step / 1000.0);
+        // a crashed racer stays where it is
+        if self.alive[0] {
+            self.player
+                .gravity_and_move(step / 1000.0, &self.physics, self.wind);
+            if self.player.apply_buffered_flap(&self.physics) {
+                self.flapped();
+            }
+        }
+        if self.run == RunKind::Race && self.alive[1] {
+            self.player2
+                .gravity_and_move(step / 1000.0, &self.physics, self.wind);
+            if self.player2.apply_buffered_flap(&self.physics) {
+                self.flapped();
+            }
+        }
+        self.trace.push(self.player.y);
+        if self.magnet_timer > 0.0 {
+            for coin in &mut self.coin_pickups {
+                coin.pull_towards(&self.player, MAGNET_RADIUS);
+            }
+        }
+        // the world moves on by the same simulated step as the players
+        update_all(&mut self.obstacles, step / 1000.0);
+        update_all(&mut self.power_ups, step / 1000.0);
+        update_all(&mut self.coin_pickups, step / 1000.0);
+        if self.multiplier_flash > 0 {
+            self.multiplier_flash -= 1;
+        }
+
+        self.pick_up();
+        self.collide(start_x);
+    }
+
+    // what happens to whoever hit something this physics step
+    fn collide(&mut self, start_x: [i32; 2]) {
+        // godmode flies through everything, it only keeps to the screen
+        if self.godmode() {
+            self.player.keep_on_screen(self.ground_y());
+            self.player2.keep_on_screen(self.ground_y());
+        } else if self.run == RunKind::Race {
+            self.race_collisions(start_x);
+        } else if self.run == RunKind::Zen {
+            // flashes to show it would have been a crash, and is kept on screen
+            if self.crashed(&self.player, start_x[0]) && !self.player.is_invulnerable() {
+                self.player.absorb_hit(self.ground_y());
+            }
+        } else if self.crashed(&self.player, start_x[0]) && !self.player.is_invulnerable() {
+            // a shield saves the player once, then lives are used up
+            self.shake = self.config.shake_intensity / 2.0;
+            if self.player.shielded {
+                self.player.absorb_hit(self.ground_y());
+            } else if self.run == RunKind::TimeAttack {
+                // a crash only costs time
+                self.time_left = f32::max(0.0, self.time_left - TIME_ATTACK_PENALTY);
+                self.audio.play_crash();
+                let y = self.safe_row();
+                self.player.respawn(y);
+            } else {
+                self.lives -= 1;
+                if self.lives > 0 {
+                    self.audio.play_crash();
+                    let y = self.safe_row();
+                    self.player.respawn(y);
+                } else {
+                    self.crash();
+                }
+            }
+        }
+    }
+
+    fn godmode(&self) -> bool {
+        self.cheats.is_some_and(|cheats| cheats.godmode)
+    }
+
+    // whether the current run has used a cheat
+    fn cheated(&self) -> bool {
+        self.cheats.is_some_and(|cheats| cheats.used)
+    }
+
+    // a flap that lifted a player during a run
+    fn flapped(&mut self) {
+        self.audio.play_flap();
+        self.stats.flaps += 1;
+    }
+
+    // points for every obstacle the player flies past
+    fn score_passes(&mut self) {
+        for obstacle in &mut self.obstacles {
+            if !obstacle.passed && self.player.x > obstacle.right_x() {
+                // (6)
+                obstacle.passed = true;
+                self.stats.obstacles_passed += 1;
+
+                // squeezing past near a gap edge builds the combo,
+                // any looser pass drops it back to 1x
+                let distance = obstacle.gap_distance(self.player.y);
+                if distance >= obstacle.size / 2 - CLOSE_PASS_MARGIN {
+                    self.combo += 1;
+                    let multiplier = i32::min(MAX_MULTIPLIER, 1 + self.combo);
+                    if multiplier > self.multiplier {
+                        self.multiplier_flash = MULTIPLIER_FLASH_FRAMES;
+                    }
+                    self.multiplier = multiplier;
+                } else {
+                    self.combo = 0;
+                    self.multiplier = 1;
+                }
+                self.score += self.multiplier;
+                self.popups.push(ScorePopup::new(
+                    POPUP_X,
+                    self.player.y,
+                    format!("+{}", self.multiplier),
+                ));
+
+                // a near miss is worth a bonus on top, whatever the combo
+                let near_miss = distance >= obstacle.size / 2 - NEAR_MISS_MARGIN;
+                if near_miss {
+                    self.score += NEAR_MISS_BONUS;
+                    self.popups.push(ScorePopup::new(
+                        POPUP_X,
+                        self.player.y - 1,
+                        format!("+{NEAR_MISS_BONUS} BONUS"),
+                    ));
+                }
+                if obstacle.kind == ObstacleKind::Boss {
+                    self.score += BOSS_BONUS;
+                    self.popups.push(ScorePopup::new(
+                        POPUP_X,
+                        self.player.y + 1,
+                        format!("+{BOSS_BONUS} BOSS"),
+                    ));
+                }
+                self.achievements.passed(near_miss);
+            }
+        }
+    }
+
+    // announce whatever the run has just unlocked, and let the last one fade
+    fn unlock_achievements(&mut self, dt: f32) {
+        if let Some((_, seconds_left)) = &mut self.toast {
+            *seconds_left -= dt;
+            if *seconds_left <= 0.0 {
+                self.toast = None;
+            }
+        }
+        if let Some(achievement) = self.achievements.check(self.total_score()) {
+            self.toast = Some((achievement, TOAST_SECONDS));
+        }
+    }
+
+    // a racer scores a point for every obstacle flown past since start_x,
+    // past its last column like in score_passes
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn score_race(&mut self, start_x: [i32; 2]) {
+        let end_x = [self.player.x, self.player2.x];
+        for ((score, start), end) in self.race_scores.iter_mut().zip(start_x).zip(end_x) {
+            let passed = self
+                .obstacles
+                .iter()
+                .filter(|obstacle| obstacle.right_x() >= start && obstacle.right_x() < end)
+                .count();
+            *score += passed as i32;
+            self.stats.obstacles_passed += passed as u64;
+        }
+    }
+
+    // score the course gets harder with, gaps shrink and the world speeds up
+    // it is the leading racer's score in a race
+    fn course_score(&self) -> i32 {
+        if self.run == RunKind::Race {
+            i32::max(self.race_scores[0], self.race_scores[1])
+        } else {
+            self.score
+        }
+    }
+
+    // true if the player is inside an obstacle or off the screen
+    // from_x is where it was before this frame's physics steps
+    fn crashed(&self, player: &Player, from_x: i32) -> bool {
+        // both the ground and the ceiling are deadly
+        let out_of_bounds = player.y <= 0 || player.y >= self.ground_y();
+        out_of_bounds
+            || self
+                .obstacles
+                .iter()
+                .any(|obstacle| obstacle.hit_obstacle(player, from_x))
+    }
+
+    // top row of the ground along the bottom of the screen
+    fn ground_y(&self) -> i32 {
+        self.config.screen.height - GROUND_ROWS
+    }
+
+    // middle of the gap the player is heading for, or of the screen before any
+    fn safe_row(&self) -> i32 {
+        self.obstacle_ahead()
+            .map_or(self.config.screen.height / 2, |obstacle| obstacle.gap_y)
+    }
+
+    // a crashed racer drops out, the race is over once both are out
+    fn race_collisions(&mut self, start_x: [i32; 2]) {
+        let crashed = [
+            self.crashed(&self.player, start_x[0]),
+            self.crashed(&self.player2, start_x[1]),
+        ];
+        let mut dropped_out = false;
+        for (alive, crashed) in self.alive.iter_mut().zip(crashed) {
+            if *alive && crashed {
+                *alive = false;
+                dropped_out = true;
+            }
+        }
+        if !self.alive.contains(&true) {
+            self.crash();
+        } else if dropped_out {
+            self.audio.play_crash();
+            self.shake = self.config.shake_intensity / 2.0;
+        }
+    }
+
+    // spawn a new obstacle at the right edge once the newest one
+    // has scrolled the configured spacing towards the player
+    fn spawn(&mut self) {
+        let spawn_x = self.leader().x + self.config.screen.width;
+        let newest_x = self
+            .obstacles
+            .last()
+            .map_or(i32::MIN, |obstacle| obstacle.x);
+        let spacing = self.config.obstacle_spacing;
+        if newest_x <= spawn_x - spacing {
+            let score = self.course_score();
+            // the first obstacle after passing a milestone is a boss,
+            // then it is back to the usual ones
+            // a handcrafted course is left as it was designed
+            let on_course = self.course_position < self.course.len();
+            let obstacle = if score >= self.next_boss_score && !on_course {
+                self.next_boss_score += BOSS_EVERY;
+                Obstacle::boss(
+                    spawn_x,
+                    score,
+                    self.config.base_gap,
+                    self.config.screen.height,
+                    &mut self.random,
+                )
+            } else {
+                self.next_obstacle(spawn_x, score)
+            };
+            let random = &mut self.random;
+
+            // a race or zen is about flying only, nothing to collect
+            if matches!(self.run, RunKind::Race | RunKind::Zen) {
+                self.obstacles.push(obstacle);
+                return;
+            }
+
+            // sometimes put a coin right in the middle of the gap
+            if random.range(0, COIN_CHANCE) == 0 {
+                self.coin_pickups
+                    .push(Coin::new(obstacle.x, obstacle.gap_y));
+            }
+            self.obstacles.push(obstacle);
+
+            // sometimes leave a power-up halfway to the new obstacle,
+            // hardcore goes without
+            if self.run != RunKind::Hardcore && random.range(0, POWERUP_CHANCE) == 0 {
+                self.power_ups.push(PowerUp::new(
+                    spawn_x - spacing / 2,
+                    random.range(
+                        self.config.screen.height / 5,
+                        self.config.screen.height * 4 / 5,
+                    ),
+                    PowerUpKind::random(random),
+                ));
+            }
+        }
+    }
+
+    // the next obstacle of the course at x, or once it is over
+    // a random one
+    fn next_obstacle(&mut self, x: i32, score: i32) -> Obstacle {
+        let (base_gap, height) = (self.config.base_gap, self.config.screen.height);
+        if let Some(&planned) = self.course.get(self.course_position) {
+            self.course_position += 1;
+            return Obstacle::planned(x, planned, height);
+        }
+        Obstacle::new(x, score, base_gap, height, &mut self.random)
+    }
+
+    // let the current gust blow out, or sometimes start a new one
+    fn blow_wind(&mut self, dt: f32) {
+        if self.wind_timer > 0.0 {
+            self.wind_timer -= dt;
+            if self.wind_timer <= 0.0 {
+                self.wind = 0.0;
+            }
+        } else if self.random.range(0, WIND_CHANCE) == 0 {
+            self.wind = if self.random.range(0, 2) == 0 {
+                -WIND_STRENGTH
+            } else {
+                WIND_STRENGTH
+            };
+            self.wind_timer = WIND_SECONDS;
+        }
+    }
+
+    // apply a collected power-up
+    fn collect(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::Shield => self.player.shielded = true,
+            PowerUpKind::SlowMo => self.slowmo_timer = SLOWMO_SECONDS,
+            PowerUpKind::GravityFlip => self.flip_timer = FLIP_SECONDS,
+            PowerUpKind::Magnet => self.magnet_timer = MAGNET_SECONDS,
+        }
+    }
+
+    // colors of the theme picked in the settings
+    fn theme(&self) -> &'static Theme {
+        if self.settings.accessibility {
+            &HIGH_CONTRAST
+        } else {
+            self.settings.theme.theme()
+        }
+    }
+
+    // color of the less important lines, like the seed on the death screen
+    // gray is hard to make out with low vision, so it is white then
+    fn hint_color(&self) -> (u8, u8, u8) {
+        if self.settings.accessibility {
+            WHITE
+        } else {
+            GRAY
+        }
+    }
+
+    // the score line of the HUD into the text buffer, as the run counts it
+    fn write_hud_score(&mut self) {
+        self.text.clear();
+        if self.run == RunKind::Race {
+            let _ = write!(
+                self.text,
+                "P1: {}  P2: {}",
+                self.race_scores[0], self.race_scores[1]
+            );
+            return;
+        }
+        if self.run == RunKind::Zen {
+            self.text.push_str("Zen practice  (B) Back to menu");
+            return;
+        }
+        let _ = match self.config.scoring {
+            ScoringMode::Obstacles => {
+                write!(self.text, "Score: {}  Coins: {}", self.score, self.coins)
+            }
+            ScoringMode::Distance => write!(self.text, "Distance: {}", self.total_score()),
+            ScoringMode::Coins => write!(self.text, "Coins: {}", self.coins),
+        };
+    }
+
+    // draw the player, obstacles and HUD without advancing anything
+    fn render_playfield<R: Renderer>(&mut self, ctx: &mut R) {
+        let theme = self.theme();
+        let camera_x = self.leader().x;
+        // clear window with the sky color for this time of day and biome
+        // player.x advances once per physics frame, so it doubles as the clock
+        #[allow(clippy::cast_precision_loss)]
+        let sky = sky_color(camera_x as f32 / DAY_LENGTH as f32, &theme.sky);
+        // the biomes' tints would lift the sky off black
+        let sky = if self.settings.accessibility {
+            sky
+        } else {
+            self.background.sky(sky)
+        };
+        ctx.cls_bg(sky);
+        self.background.render(ctx, camera_x, sky, theme);
+        render_ground(ctx, camera_x, theme);
+
+        // the ghost flies the same columns as the player,
+        // so it is always drawn in the player's column, until its trace ends
+        // it goes first so the player is drawn on top
+        if let Some(&ghost_y) = self.ghost.get(self.trace.len().saturating_sub(1)) {
+            draw::set(ctx, 0, ghost_y, theme.ghost, BLACK, glyph::GHOST);
+        }
+
+        render_all(ctx, &self.obstacles, camera_x, theme); // (5)
+        render_all(ctx, &self.power_ups, camera_x, theme);
+        render_all(ctx, &self.coin_pickups, camera_x, theme);
+        if self.run == RunKind::Fog {
+            self.render_fog(ctx);
+        }
+
+        // update player position information
+        self.player
+            .render(ctx, camera_x, self.blend, theme.player, theme);
+        if self.run == RunKind::Race {
+            self.player2
+                .render(ctx, camera_x, self.blend, theme.player2, theme);
+        }
+
+        // the world is drawn first so it never hides the HUD
+        self.render_hud(ctx, theme);
+        // on top of everything, they are only there to be read
+        for popup in &self.popups {
+            popup.render(ctx, theme);
+        }
+    }
+
+    // darken every cell further than fog_radius from the player,
+    // who is always drawn in the first column
+    fn render_fog<R: Renderer>(&self, ctx: &mut R) {
+        let radius = self.config.fog_radius;
+        let (width, height) = ctx.size();
+        for y in 0..height {
+            for x in 0..width {
+                let dy = y - self.player.y;
+                if x * x + dy * dy > radius * radius {
+                    ctx.set(x, y, BLACK, BLACK, glyph::BLANK);
+                }
+            }
+        }
+    }
+
+    // the second line of the HUD, score, speed and whether it is muted
+    fn render_hud_score<R: Renderer>(&mut self, ctx: &mut R, theme: &Theme) {
+        self.write_hud_score();
+        let _ = write!(
+            self.text,
+            "  Speed: {}",
+            GameConfig::speed_level(self.course_score())
+        );
+        if self.muted {
+            self.text.push_str(" [muted]");
+        }
+        if self.settings.accessibility {
+            // the score in the middle of the top, dark on light so it
+            // can't be lost against anything flying behind it
+            self.text.insert(0, ' ');
+            self.text.push(' ');
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            let length = self.text.chars().count() as i32;
+            let x = self.config.screen.width / 2 - length / 2;
+            self.print_text_on(ctx, x, 1, BLACK, theme.text);
+        } else {
+            self.print_text(ctx, 0, 1, theme.text); // (4)
+        }
+    }
+
+    // scores, hints and timers over the top left of the playfield
+    fn render_hud<R: Renderer>(&mut self, ctx: &mut R, theme: &Theme) {
+        // print hint message and player total score
+        self.text.clear();
+        if self.run == RunKind::Race {
+            let _ = write!(
+                self.text,
+                "Press {:?} / {:?} to flap.",
+                self.settings.keys.flap, self.settings.keys.flap2
+            );
+        } else {
+            let _ = write!(self.text, "Press {:?} to flap.", self.settings.keys.flap);
+        }
+        self.print_text(ctx, 0, 0, theme.text);
+        self.render_hud_score(ctx, theme);
+        self.text.clear();
+        let _ = write!(self.text, "Press {:?} to pause.", self.settings.keys.pause);
+        self.print_text(ctx, 0, 2, theme.text);
+        if self.player.shielded {
+            ctx.print_color(0, 3, theme.shield, BLACK, "Shield up");
+        }
+        // a heart for every life left, in the top right corner
+        if !matches!(self.run, RunKind::Race | RunKind::Zen | RunKind::TimeAttack) {
+            for life in 1..=self.lives {
+                draw::set(
+                    ctx,
+                    self.config.screen.width - life,
+                    0,
+                    theme.heart,
+                    BLACK,
+                    glyph::HEART,
+                );
+            }
+        }
+        if self.multiplier > 1 {
+            // blink while the multiplier has just gone up
+            let color = if self.multiplier_flash % 4 >= 2 {
+                theme.text
+            } else {
+                theme.coin
+            };
+            self.text.clear();
+            let _ = write!(self.text, "Combo x{}", self.multiplier);
+            self.print_text(ctx, 0, 5, color);
+        }
+        if self.slowmo_timer > 0.0 {
+            self.text.clear();
+            let _ = write!(self.text, "Slow-mo: {:.1}s", self.slowmo_timer);
+            self.print_text(ctx, 0, 4, theme.slowmo);
+        }
+        if self.flip_timer > 0.0 {
+            self.text.clear();
+            let _ = write!(self.text, "Gravity flipped: {:.1}s", self.flip_timer);
+            self.print_text(ctx, 0, 6, theme.flip);
+        }
+        if self.magnet_timer > 0.0 {
+            self.text.clear();
+            let _ = write!(self.text, "Magnet: {:.1}s", self.magnet_timer);
+            self.print_text(ctx, 0, 8, theme.magnet);
+        }
+        if self.godmode() {
+            ctx.print_color(0, 9, theme.heart, BLACK, "GODMODE");
+        }
+
+        if self.run == RunKind::Daily {
+            self.text.clear();
+            let _ = write!(
+                self.text,
+                "Daily {}  {}  Seed: {}",
+                self.daily_date, self.config.screen, self.seed
+            );
+            self.print_text(ctx, 0, 7, theme.text);
+        }
+        if let Some(challenge) = self.challenge {
+            self.text.clear();
+            let _ = write!(self.text, "To beat: {}", challenge.score);
+            self.print_text(ctx, 0, 7, theme.text);
+        }
+        if self.run == RunKind::Hardcore {
+            ctx.print_color(0, 7, theme.heart, BLACK, "HARDCORE");
+        }
+        if self.run == RunKind::TimeAttack {
+            self.text.clear();
+            let _ = write!(self.text, "Time: {:.1}s", self.time_left);
+            self.print_text(ctx, 0, 7, theme.text);
+        }
+
+        if let Some((achievement, _)) = self.toast {
+            self.text.clear();
+            let _ = write!(self.text, "Achievement unlocked: {}", achievement.name());
+            self.print_text_centered(ctx, self.config.screen.height - 3, theme.coin);
+        }
+
+        // show which way the wind blows so the player can brace for it,
+        // under everything else so it can't cover the flip timer
+        if self.wind < 0.0 {
+            ctx.print_color(0, 10, theme.scenery[1], BLACK, "Wind ↑");
+        } else if self.wind > 0.0 {
+            ctx.print_color(0, 10, theme.scenery[1], BLACK, "Wind ↓");
+        }
+    }
+
+    // frame rate and frame time in the bottom right corner,
+    // well away from the HUD in the top left
+    // the frame rate and "avg" are over the last few frames so they hold
+    // still, "now" is the last frame on its own
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn render_fps(&mut self, ctx: &mut BTerm) {
+        self.text.clear();
+        let average = self.frame_times.average();
+        let fps = if average > 0.0 { 1000.0 / average } else { 0.0 };
+        let _ = write!(
+            self.text,
+            "FPS: {fps:.0}  {average:.1} ms avg  {:.1} ms now",
+            self.frame_times.latest()
+        );
+        let length = self.text.chars().count() as i32;
+        let (x, y) = (
+            self.config.screen.width - length,
+            self.config.screen.height - 1,
+        );
+        self.print_text(ctx, x, y, self.hint_color());
+    }
+
+    // bracket-lib writes the PNG once the frame has been presented
+    // and panics if it can't, so the file is created here first
+    // to find out whether it can be written
+    fn take_screenshot(&mut self, ctx: &mut BTerm) {
+        let file = format!("screenshot-{}.png", timestamp());
+        let message = match std::fs::File::create(&file) {
+            Ok(_) => {
+                ctx.screenshot(&file);
+                format!("Screenshot saved to {file}")
+            }
+            Err(err) => {
+                eprintln!("Could not save screenshot to {file}: {err}");
+                format!("Could not save screenshot: {err}")
+            }
+        };
+        self.screenshot_message = Some((message, SCREENSHOT_MESSAGE_SECONDS));
+    }
+
+    fn render_screenshot_message(&mut self, ctx: &mut BTerm) {
+        let color = self.theme().text;
+        if let Some((message, seconds_left)) = &mut self.screenshot_message {
+            *seconds_left -= ctx.frame_time_ms / 1000.0;
+            if *seconds_left <= 0.0 {
+                self.screenshot_message = None;
+            } else {
+                let y = self.config.screen.height - 2;
+                ctx.print_color_centered(y, color, BLACK, message.as_str());
+            }
+        }
+    }
+
+    fn countdown(&mut self, ctx: &mut BTerm) {
+        // show where everything is while the world stays frozen
+        self.render_playfield(ctx);
+
+        // the countdown runs on real time, frame_time isn't touched
+        // so none of it leaks into the physics once play starts
+        self.countdown -= ctx.frame_time_ms / 1000.0;
+        if self.countdown <= 0.0 {
+            self.transition_to(GameMode::Playing);
+        } else {
+            #[allow(clippy::cast_possible_truncation)]
+            let seconds = self.countdown.ceil() as i32;
+            self.text.clear();
+            let _ = write!(self.text, "{seconds}");
+            self.print_text_centered(ctx, self.config.screen.height / 2, WHITE);
+        }
+    }
+
+    // set up the practice obstacle, also used to try again after a crash
+    fn start_tutorial(&mut self) {
+        self.physics = Difficulty::Easy.physics();
+        self.player = Player::new(START_X, self.config.screen.height / 2, &self.physics);
+        self.background.reset();
+        self.frame_time = 0.0;
+        self.obstacles = vec![Obstacle::tutorial(
+            self.player.x + self.config.screen.width / 2,
+            self.config.screen.height,
+        )];
+        self.tutorial = TutorialStep::Flap;
+        self.transition_to(GameMode::Tutorial);
+    }
+
+    fn tutorial(&mut self, ctx: &mut BTerm) {
+        let flapped = ctx.key == Some(self.settings.keys.flap) || clicked(ctx);
+        match self.tutorial {
+            TutorialStep::Flap => {
+                if flapped {
+                    self.player.flap(&self.physics);
+                    self.audio.play_flap();
+                    self.tutorial = TutorialStep::Gap;
+                }
+            }
+            TutorialStep::Gap => {
+                // the same physics as a run, only slower and without wind
+                #[allow(clippy::cast_precision_loss)]
+                let max_time = TUTORIAL_FRAME_DURATION * self.config.max_steps_per_frame as f32;
+                self.frame_time = f32::min(self.frame_time + ctx.frame_time_ms, max_time);
+                self.player.flap_held = self.flaps_held()[0];
+                // checked after every step, like a run
+                let mut crashed = false;
+                while !crashed && self.frame_time >= TUTORIAL_FRAME_DURATION {
+                    self.frame_time -= TUTORIAL_FRAME_DURATION;
+                    let start_x = self.player.x;
+                    self.player
+                        .gravity_and_move(FRAME_DURATION / 1000.0, &self.physics, 0.0);
+                    if self.player.apply_buffered_flap(&self.physics) {
+                        self.audio.play_flap();
+                    }
+                    crashed = self.crashed(&self.player, start_x);
+                }
+                self.player.expire_flap_buffer(ctx.frame_time_ms / 1000.0);
+                if flapped && self.player.press_flap(&self.physics) {
+                    self.audio.play_flap();
+                }
+
+                let passed = self
+                    .obstacles
+                    .iter()
+                    .all(|obstacle| self.player.x > obstacle.x);
+                if crashed {
+                    // no game over in the tutorial, just try again
+                    self.audio.play_crash();
+                    self.start_tutorial();
+                } else if passed {
+                    self.tutorial = TutorialStep::Done {
+                        seconds_left: TUTORIAL_DONE_SECONDS,
+                    };
+                }
+            }
+            TutorialStep::Done { seconds_left } => {
+                let seconds_left = seconds_left - ctx.frame_time_ms / 1000.0;
+                if seconds_left <= 0.0 {
+                    self.transition_to(GameMode::Menu);
+                } else {
+                    self.tutorial = TutorialStep::Done { seconds_left };
+                }
+            }
+        }
+
+        self.render_tutorial(ctx);
+    }
+
+    // the practice obstacle with its gap lit up, and what to do next
+    fn render_tutorial<R: Renderer>(&mut self, ctx: &mut R) {
+        let theme = self.theme();
+        let sky = sky_color(0.0, &theme.sky);
+        ctx.cls_bg(sky);
+        self.background.render(ctx, self.player.x, sky, theme);
+        for obstacle in &self.obstacles {
+            obstacle.render(ctx, self.player.x, theme);
+            // light the gap up until the player is through it
+            if !matches!(self.tutorial, TutorialStep::Done { .. }) {
+                let half_size = obstacle.size / 2;
+                for y in obstacle.gap_y - half_size..obstacle.gap_y + half_size {
+                    draw::set_bg(ctx, obstacle.x - self.player.x, y, theme.ghost);
+                }
+            }
+        }
+        let blend = self.frame_time / TUTORIAL_FRAME_DURATION;
+        self.player
+            .render(ctx, self.player.x, blend, theme.player, theme);
+
+        self.text.clear();
+        let _ = match self.tutorial {
+            TutorialStep::Flap => write!(self.text, "Press {:?} to flap!", self.settings.keys.flap),
+            TutorialStep::Gap => write!(self.text, "Fly through the gap!"),
+            TutorialStep::Done { .. } => write!(self.text, "Well done!"),
+        };
+        self.print_text_centered(ctx, self.config.screen.height / 4, theme.text);
+    }
+
+    fn dying(&mut self, ctx: &mut BTerm) {
+        // the world stays frozen behind the explosion, shaking from the crash
+        let dt = ctx.frame_time_ms / 1000.0;
+        let (dx, dy) = self.shake_offset(dt);
+        let mut shaken = Offset::new(ctx, dx, dy);
+        self.render_playfield(&mut shaken);
+
+        let theme = self.theme();
+        let camera_x = self.leader().x;
+        update_all(&mut self.particles, dt);
+        render_all(&mut shaken, &self.particles, camera_x, theme);
+        self.particles.retain(Particle::is_alive);
+
+        self.death_timer -= dt;
+        if self.death_timer <= 0.0 {
+            self.particles.clear();
+            self.fade(State::game_over);
+        }
+    }
+
+    // fade out, change screen with `then` and fade back in
+    // the screen being left keeps running without input until it is black,
+    // asking for another fade meanwhile does nothing
+    fn fade(&mut self, then: fn(&mut State)) {
+        if self.transition.is_none() {
+            self.transition = Some(Transition::new(then));
+        }
+    }
+
+    // move the fade along and darken the frame just drawn to match
+    fn update_transition(&mut self, ctx: &BTerm) {
+        let Some(transition) = &mut self.transition else {
+            return;
+        };
+        let then = transition.update(ctx.frame_time_ms / 1000.0);
+        darken(transition.darkness());
+        if transition.is_done() {
+            self.transition = None;
+        }
+        if let Some(then) = then {
+            then(self);
+        }
+    }
+
+    // the frozen game dimmed underneath a box of what can be done
+    fn render_pause_menu(&mut self, ctx: &mut BTerm) {
+        self.render_playfield(ctx);
+        darken(PAUSE_DIM);
+        let theme = self.theme();
+        let (width, height) = (PAUSE_BOX_WIDTH, 6);
+        let (x, y) = (
+            (self.config.screen.width - width) / 2,
+            (self.config.screen.height - height) / 2,
+        );
+        ctx.draw_box(x, y, width - 1, height - 1, theme.text, BLACK);
+        ctx.print_color_centered(y + 1, theme.text, BLACK, "PAUSED");
+        self.text.clear();
+        let _ = write!(self.text, "Resume ({:?})", self.settings.keys.pause);
+        self.print_text_centered(ctx, y + 2, theme.text);
+        ctx.print_color_centered(y + 3, theme.text, BLACK, "Restart (R)");
+        self.text.clear();
+        let _ = write!(self.text, "Quit to Menu ({:?})", self.settings.keys.quit);
+        self.print_text_centered(ctx, y + 4, theme.text);
+    }
+
+    fn paused(&mut self, ctx: &mut BTerm) {
+        self.render_pause_menu(ctx);
+
+        // no physics and no frame_time while paused, so resuming
+        // carries on exactly where the player left off
+        match ctx.key {
+            Some(key) if key == self.settings.keys.pause => self.transition_to(GameMode::Playing),
+            Some(VirtualKeyCode::R) => self.fade(State::restart),
+            // the run is dropped without a score, like a zen run
+            // only the totals played so far are kept
+            Some(key) if key == self.settings.keys.quit => {
+                self.save_stats();
+                self.fade(|state| state.transition_to(GameMode::Menu));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl GameState for State {
+    // must implement tick method
+    // &mut self: allows to change game status instance
+    // ctx: provide a window into current running bracket-terminal, accessing information like mouse,
+    // keyboard etc and sending commands to draw the window
+    // short for "context", interacting with game display
+    fn tick(&mut self, ctx: &mut BTerm) {
+        // a controller button stands in for the matching key,
+        // so every screen handles both the same way
+        // polled every tick to keep the event queue drained
+        let button = self.gamepad.poll();
+        if ctx.key.is_none() {
+            ctx.key = button.and_then(|button| self.key_for_button(button));
+        }
+
+        // closing the window comes in as an event, see run
+        let mut close_requested = false;
+        INPUT.lock().for_each_message(|event| {
+            if let BEvent::CloseRequested = event {
+                close_requested = true;
+            }
+        });
+        if close_requested {
+            self.quit(ctx);
+        }
+
+        // nothing can be pressed mid-fade, so it can't be skipped
+        if self.transition.is_some() {
+            ctx.key = None;
+            ctx.left_click = false;
+        }
+
+        // mute or unmute from any screen
+        if ctx.key == Some(self.settings.keys.mute) {
+            self.muted = !self.muted;
+            self.audio.set_muted(self.muted);
+        }
+        if ctx.key == Some(self.settings.keys.fps) {
+            self.show_fps = !self.show_fps;
+        }
+        self.frame_times.push(ctx.frame_time_ms);
+        // Ctrl+I, taken so it isn't typed into a name or anything
+        if ctx.control && ctx.key == Some(VirtualKeyCode::I) {
+            if let Some(cheats) = &mut self.cheats {
+                cheats.godmode = !cheats.godmode;
+                cheats.used |= cheats.godmode;
+                ctx.key = None;
+            }
+        }
+
+        match self.mode {
+            GameMode::Splash { seconds_left } => self.splash(ctx, seconds_left),
+            GameMode::Menu => self.main_menu(ctx),
+            GameMode::Dying => self.dying(ctx),
+            GameMode::End => self.dead(ctx),
+            GameMode::Countdown => self.countdown(ctx),
+            GameMode::Playing => self.play(ctx),
+            GameMode::Paused => self.paused(ctx),
+            GameMode::EnterInitials => self.enter_initials(ctx),
+            GameMode::EnterCode => self.enter_code(ctx),
+            GameMode::Leaderboard => self.leaderboard(ctx),
+            GameMode::Achievements => self.achievements(ctx),
+            GameMode::Settings => self.settings(ctx),
+            GameMode::Tutorial => self.tutorial(ctx),
+            GameMode::Demo => self.demo(ctx),
+            GameMode::ConfirmQuit => self.confirm_quit(ctx),
+            GameMode::ConfirmReset => self.confirm_reset(ctx),
+        }
+        self.update_transition(ctx);
+
+        // drawn last so it stays on top of every screen
+        if self.show_fps {
+            self.render_fps(ctx);
+        }
+        self.render_screenshot_message(ctx);
+        // after everything else is drawn, and before this frame's message
+        // is, so the screenshot shows the frame the key was pressed on
+        if ctx.key == Some(self.settings.keys.screenshot) {
+            self.take_screenshot(ctx);
+        }
+
+        // music only plays during a run, it is paused on every other screen
+        self.audio
+            .set_music_playing(matches!(self.mode, GameMode::Playing));
+    }
+}
+
+// what to tell the player when the game window can't be opened
+const NO_WINDOW: &str = "Could not open a game window; is a display available?";
+
+// false on Linux and the BSDs when there is no X11 or Wayland display
+// to open a window on, other systems always have one
+fn display_available() -> bool {
+    !cfg!(all(unix, not(target_os = "macos")))
+        || std::env::var_os("DISPLAY").is_some()
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+// start the game, the whole of main
+// errors are bracket-lib failing to open the window
+#[allow(clippy::missing_errors_doc)]
+pub fn run() -> BError {
+    // --width and --height change the size, the saved one otherwise
+    let settings = Settings::load();
+    let mut args = Args::parse(settings.screen);
+
+    // --stats prints the totals, no window needed
+    if args.stats {
+        Stats::load().print();
+        return Ok(());
+    }
+
+    // --simulate plays runs headless for balancing, no window needed
+    if let Some(runs) = args.simulate {
+        simulate::report(runs);
+        return Ok(());
+    }
+
+    // --replay flies a recorded run again, on its own screen and difficulty
+    // a replay that can't be read fails the launch, so scripts notice
+    let replay = match &args.replay {
+        Some(path) => match Replay::load(path) {
+            Ok(replay) => Some(replay),
+            Err(err) => return Err(format!("Could not read replay {path}: {err}").into()),
+        },
+        None => None,
+    };
+    if let Some(replay) = &replay {
+        args.screen = replay.screen;
+    }
+
+    // --course starts every run with the obstacles of a file,
+    // like a replay one that can't be read fails the launch
+    let course = match &args.course {
+        Some(path) => match load_course(path) {
+            Ok(course) => course,
+            Err(err) => return Err(format!("Could not read course {path}: {err}").into()),
+        },
+        None => Vec::new(),
+    };
+
+    //Result.unwrap
+    // build the terminal area
+    // bracket-lib aborts instead of failing when X11 has no display,
+    // so over ssh or on a headless machine that is checked first
+    if !display_available() {
+        eprintln!("{NO_WINDOW}");
+        return Err("neither DISPLAY nor WAYLAND_DISPLAY is set".into());
+    }
+    let context = BTermBuilder::simple(args.screen.width, args.screen.height)?
+        .with_title("Flappy Dragon")
+        .with_fullscreen(args.fullscreen)
+        .build()
+        .inspect_err(|_| eprintln!("{NO_WINDOW}"))?;
+
+    // the saved difficulty and scoring on the screen asked for
+    let mut config = GameConfig::new(args.screen, settings.difficulty);
+    config.scoring = settings.scoring;
+    // --seed replays the same run every time
+    let seed = args
+        .seed
+        .unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    // without the event queue bracket-lib ends the loop as soon as the
+    // window is closed, with it tick gets the chance to save first
+    INPUT.lock().activate_event_queue();
+    let mut state = State {
+        fixed_seed: args.seed,
+        course,
+        // godmode is only there for testing courses, with --debug
+        cheats: args.debug.then(Cheats::default),
+        mode: GameMode::Splash {
+            seconds_left: SPLASH_SECONDS,
+        },
+        ..State::with_settings(config, settings, seed)
+    };
+    if let Some(replay) = replay {
+        state.watch(replay);
+    }
+    main_loop(context, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::SCREEN_HEIGHT;
+    use renderer::Recorder;
+
+    // a new game on the menu, silent, with nothing to unlock
+    // and no records read from or saved to the working directory
+    fn quiet_state() -> State {
+        let mut state = State::in_memory(GameConfig::default(), Settings::default(), 1);
+        state.audio.set_muted(true);
+        state
+    }
+
+    // a normal run that has just started, quiet and with nothing to unlock
+    // so nothing is saved, and no gust to push the player off course
+    fn playing_state() -> State {
+        let mut state = quiet_state();
+        state.restart();
+        state.transition_to(GameMode::Playing);
+        state.wind_timer = f32::MAX;
+        state
+    }
+
+    // a rendered frame lasting `ms` real milliseconds, with nothing pressed
+    fn frame(ms: f32) -> FrameInput {
+        FrameInput {
+            frame_time_ms: ms,
+            ..FrameInput::default()
+        }
+    }
+
+    // the player climbing a row every physics frame from row y, without gravity
+    fn climbing_from(state: &mut State, y: i32) {
+        state.physics.gravity = 0.0;
+        state.player = Player::new(START_X, y, &state.physics);
+        state.player.velocity = -1.0;
+    }
+
+    // a still pipe at x with the gap rows 24 ..= 26
+    fn pipe(x: i32) -> Obstacle {
+        Obstacle::planned(x, CourseObstacle { gap_y: 25, size: 3 }, SCREEN_HEIGHT)
+    }
+
+    // three physics steps in one frame: into the wall on the first,
+    // into the gap by the last, which is all the end of the frame shows
+    #[test]
+    fn a_wall_crossed_early_in_a_frame_is_still_hit() {
+        let mut state = playing_state();
+        climbing_from(&mut state, 28);
+        state.obstacles = vec![pipe(START_X + 1)];
+        state.lives = 1;
+        state.update(frame(3.0 * state.config.frame_duration));
+        assert!(matches!(state.mode, GameMode::Dying));
+        // stopped in the wall, the rest of the frame wasn't stepped
+        assert_eq!((state.player.x, state.player.y), (START_X + 1, 27));
+    }
+
+    #[test]
+    fn a_gap_flown_through_is_safe() {
+        let mut state = playing_state();
+        climbing_from(&mut state, 27);
+        state.obstacles = vec![pipe(START_X + 2)];
+        state.update(frame(3.0 * state.config.frame_duration));
+        assert!(matches!(state.mode, GameMode::Playing));
+        assert_eq!(state.lives, Difficulty::Normal.lives());
+    }
+
+    // a coin only touched on the first of the frame's steps
+    #[test]
+    fn a_coin_passed_early_in_a_frame_is_collected() {
+        let mut state = playing_state();
+        climbing_from(&mut state, 28);
+        state.coin_pickups = vec![Coin::new(START_X + 1, 27)];
+        state.update(frame(3.0 * state.config.frame_duration));
+        assert_eq!(state.player.x, START_X + 3);
+        assert_eq!(state.coins, 1);
+    }
+
+    // everything restart is meant to start over, by name
+    // the State is taken apart without `..`, so a new field doesn't compile
+    // until it is either listed here or left out as belonging to the session
+    #[allow(clippy::too_many_lines)]
+    fn run_fields(state: &State) -> Vec<(&'static str, String)> {
+        let State {
+            player,
+            player2,
+            run: _,
+            alive,
+            race_scores,
+            frame_time,
+            blend,
+            obstacles,
+            mode: _,
+            quit_from: _,
+            transition: _,
+            config,
+            score,
+            high_score: _,
+            high_score_name: _,
+            new_high_score,
+            settings: _,
+            storage: _,
+            physics,
+            audio: _,
+            muted: _,
+            show_fps: _,
+            frame_times: _,
+            screenshot_message: _,
+            cheats,
+            gamepad: _,
+            background: _,
+            random,
+            seed,
+            fixed_seed: _,
+            recording,
+            playback: _,
+            idle_time: _,
+            menu_index: _,
+            daily_date: _,
+            daily_best: _,
+            hardcore_best: _,
+            time_attack_best: _,
+            time_left,
+            next_boss_score,
+            course: _,
+            course_position,
+            power_ups,
+            slowmo_timer,
+            flip_timer,
+            magnet_timer,
+            coin_pickups,
+            coins,
+            lives,
+            combo,
+            multiplier,
+            multiplier_flash,
+            popups,
+            leaderboard: _,
+            achievements: _,
+            toast,
+            stats: _,
+            initials: _,
+            code_entry: _,
+            code_error: _,
+            challenge: _,
+            countdown: _,
+            wind,
+            wind_timer,
+            trace,
+            ghost,
+            tutorial: _,
+            text: _,
+            particles,
+            death_timer,
+            shake,
+            shake_random: _,
+        } = state;
+        // the generator can't be printed, where it has got to can
+        let next_random = random.clone().next_u64();
+        let fields: [(&'static str, &dyn std::fmt::Debug); 37] = [
+            ("player", player),
+            ("player2", player2),
+            ("alive", alive),
+            ("race_scores", race_scores),
+            ("frame_time", frame_time),
+            ("blend", blend),
+            ("obstacles", obstacles),
+            ("config", config),
+            ("score", score),
+            ("new_high_score", new_high_score),
+            ("physics", physics),
+            ("cheats", cheats),
+            ("random", &next_random),
+            ("seed", seed),
+            ("recording", recording),
+            ("time_left", time_left),
+            ("next_boss_score", next_boss_score),
+            ("course_position", course_position),
+            ("power_ups", power_ups),
+            ("slowmo_timer", slowmo_timer),
+            ("flip_timer", flip_timer),
+            ("magnet_timer", magnet_timer),
+            ("coin_pickups", coin_pickups),
+            ("coins", coins),
+            ("lives", lives),
+            ("combo", combo),
+            ("multiplier", multiplier),
+            ("multiplier_flash", multiplier_flash),
+            ("popups", popups),
+            ("toast", toast),
+            ("wind", wind),
+            ("wind_timer", wind_timer),
+            ("trace", trace),
+            ("ghost", ghost),
+            ("particles", particles),
+            ("death_timer", death_timer),
+            ("shake", shake),
+        ];
+        fields
+            .iter()
+            .map(|(name, value)| (*name, format!("{value:?}")))
+            .collect()
+    }
+
+    // a run played for a while, with every timer and counter moved along
+    // and a crash at the end, then started over
+    #[test]
+    fn restart_leaves_nothing_of_the_last_run() {
+        let mut played = playing_state();
+        played.lives = 2;
+        for frame_number in 0..400 {
+            let input = FrameInput {
+                flap: frame_number % 7 == 0,
+                held: [frame_number % 7 < 3, false],
+                ..frame(played.config.frame_duration)
+            };
+            played.update(input);
+            played.combo += 1;
+            played.multiplier = 3;
+            played.flip_timer = 1.0;
+            played.magnet_timer = 1.0;
+            played.slowmo_timer = 1.0;
+            played.wind = -0.5;
+            played
+                .popups
+                .push(ScorePopup::new(START_X, 10, "+1".to_string()));
+            if matches!(played.mode, GameMode::Dying) {
+                break;
+            }
+        }
+        played.new_high_score = true;
+        played.player.shielded = true;
+        played.restart();
+
+        let mut fresh = quiet_state();
+        fresh.restart();
+
+        for ((name, played), (_, fresh)) in run_fields(&played).iter().zip(run_fields(&fresh)) {
+            assert_eq!(played, &fresh, "{name} was kept over from the last run");
+        }
+    }
+
+    // a replay is flown on its own difficulty and scoring,
+    // without them ending up in the settings
+    #[test]
+    fn a_replay_leaves_the_settings_alone() {
+        let mut state = quiet_state();
+        let mut replay = Replay::new(7, Difficulty::Hard, state.config.screen, RunKind::Normal);
+        replay.scoring = ScoringMode::Distance;
+        state.watch(replay);
+        assert_eq!(state.config.base_gap, Difficulty::Hard.base_gap());
+        assert_eq!(state.config.scoring, ScoringMode::Distance);
+        assert_eq!(
+            (state.settings.difficulty, state.settings.scoring),
+            (Difficulty::Normal, ScoringMode::Obstacles)
+        );
+        // once it is over the player's own run is back
+        state.game_over();
+        assert_eq!(state.config.scoring, ScoringMode::Obstacles);
+        state.start(RunKind::Normal);
+        assert_eq!(state.config.base_gap, Difficulty::Normal.base_gap());
+    }
+
+    // the lives of a shared run are those of the difficulty it was set on,
+    // whatever the settings say
+    #[test]
+    fn a_shared_run_has_the_lives_of_its_difficulty() {
+        let mut state = quiet_state();
+        state.challenge = Some(ScoreCode {
+            seed: 7,
+            score: 10,
+            difficulty: Difficulty::Hard,
+            scoring: ScoringMode::Obstacles,
+            screen: state.config.screen,
+        });
+        state.start(RunKind::Normal);
+        assert_eq!(state.lives, Difficulty::Hard.lives());
+    }
+
+    // a boss only counts once a racer is past its last column, not its first
+    #[test]
+    fn a_racer_inside_a_boss_has_not_passed_it() {
+        let mut state = playing_state();
+        state.run = RunKind::Race;
+        let mut random = RandomNumberGenerator::seeded(1);
+        let boss = Obstacle::boss(START_X, 0, 20, SCREEN_HEIGHT, &mut random);
+        let right_x = boss.right_x();
+        state.obstacles = vec![boss];
+        state.player.x = right_x + 1;
+        state.player2.x = right_x;
+        state.score_race([START_X - 1, START_X - 1]);
+        assert_eq!(state.race_scores, [1, 0]);
+        // and the one still inside scores it once it gets out
+        state.player2.x = right_x + 1;
+        state.score_race([right_x + 1, right_x]);
+        assert_eq!(state.race_scores, [1, 1]);
+    }
+
+    // a run with nothing in the way of the player for a while
+    fn open_sky() -> State {
+        let mut state = playing_state();
+        state.obstacles = vec![pipe(START_X + 100)];
+        state.lives = 1;
+        state
+    }
+
+    // flipped, the player falls up and the ceiling is as deadly as the floor
+    #[test]
+    fn flipped_gravity_crashes_into_the_ceiling() {
+        let mut state = open_sky();
+        state.flip_timer = 5.0;
+        state.player = Player::new(START_X, 4, &state.physics);
+        for _ in 0..20 {
+            state.update(frame(state.config.frame_duration));
+        }
+        assert!(matches!(state.mode, GameMode::Dying));
+        assert!(state.player.y <= 0);
+    }
+
+    // a crash that doesn't end the run bounces the player back on screen
+    // from the ceiling as from the floor, whichever way gravity pulls
+    #[test]
+    fn zen_keeps_the_player_on_screen_either_way_up() {
+        for &(flip_timer, y) in &[(0.0, SCREEN_HEIGHT - 8), (5.0, 4)] {
+            let mut state = open_sky();
+            state.run = RunKind::Zen;
+            state.flip_timer = flip_timer;
+            state.player = Player::new(START_X, y, &state.physics);
+            let mut bounced = false;
+            for _ in 0..40 {
+                state.update(frame(state.config.frame_duration));
+                let y = state.player.y;
+                assert!(
+                    y >= 0 && y <= state.ground_y(),
+                    "flip {} y {}",
+                    flip_timer,
+                    y
+                );
+                // the flashing of a crash that was absorbed
+                bounced |= state.player.flash_timer > 0;
+            }
+            assert!(bounced, "flip {} never reached the edge", flip_timer);
+            assert!(matches!(state.mode, GameMode::Playing));
+        }
+    }
+
+    // one real second as 60 short frames or as 10 long ones,
+    // the accumulator steps the same physics frames either way
+    #[test]
+    fn the_player_falls_as_far_whatever_the_frame_rate() {
+        let fall = |frames: u16| {
+            let mut state = open_sky();
+            state.player = Player::new(START_X, 5, &state.physics);
+            for _ in 0..frames {
+                state.update(frame(1000.0 / f32::from(frames)));
+            }
+            (state.player.exact_y, state.player.x)
+        };
+        let (smooth, slow) = (fall(60), fall(10));
+        assert!(smooth.0 > 6.0, "only fell to {}", smooth.0);
+        assert!(
+            (smooth.0 - slow.0).abs() < 1e-3,
+            "{:?} against {:?}",
+            smooth,
+            slow
+        );
+        assert_eq!(smooth.1, slow.1);
+    }
+
+    #[test]
+    fn flying_into_the_ceiling_is_a_crash() {
+        let mut state = open_sky();
+        climbing_from(&mut state, 2);
+        state.update(frame(state.config.frame_duration));
+        assert!(
+            matches!(state.mode, GameMode::Playing),
+            "row 1 is still open"
+        );
+        state.update(frame(state.config.frame_duration));
+        assert!(matches!(state.mode, GameMode::Dying));
+        assert_eq!(state.player.y, 0);
+    }
+
+    #[test]
+    fn falling_into_the_ground_is_a_crash() {
+        let mut state = open_sky();
+        state.physics.gravity = 0.0;
+        let ground_y = state.ground_y();
+        state.player = Player::new(START_X, ground_y - 2, &state.physics);
+        state.player.velocity = 1.0;
+        state.update(frame(state.config.frame_duration));
+        assert!(
+            matches!(state.mode, GameMode::Playing),
+            "the row above the ground is open"
+        );
+        state.update(frame(state.config.frame_duration));
+        assert!(matches!(state.mode, GameMode::Dying));
+        assert_eq!(state.player.y, ground_y);
+    }
+
+    // a frame of the playfield drawn on a console the size of the screen,
+    // the recorder panics if anything is drawn off it
+    fn render_playing(state: &mut State) -> Recorder {
+        let mut ctx = Recorder::new(state.config.screen.width, state.config.screen.height);
+        state.render_playfield(&mut ctx);
+        ctx
+    }
+
+    #[test]
+    fn the_score_is_drawn_under_the_flap_hint() {
+        let mut state = playing_state();
+        state.score = 12;
+        state.coins = 3;
+        let ctx = render_playing(&mut state);
+        assert!(
+            ctx.row(0).starts_with("Press Space to flap."),
+            "{}",
+            ctx.row(0)
+        );
+        assert!(
+            ctx.row(1).starts_with("Score: 12  Coins: 3  Speed: 2"),
+            "{}",
+            ctx.row(1)
+        );
+    }
+
+    #[test]
+    fn the_wind_and_the_flip_timer_are_drawn_on_rows_of_their_own() {
+        let mut state = playing_state();
+        state.flip_timer = 2.5;
+        state.wind = -0.5;
+        let ctx = render_playing(&mut state);
+        // the scenery shows through the rest of the row
+        assert!(
+            ctx.row(6).starts_with("Gravity flipped: 2.5s "),
+            "{}",
+            ctx.row(6)
+        );
+        assert!(ctx.row(10).starts_with("Wind ↑ "), "{}", ctx.row(10));
+    }
+
+    #[test]
+    fn the_menus_say_where_the_player_is() {
+        let mut state = quiet_state();
+        let (width, height) = (state.config.screen.width, state.config.screen.height);
+        let mut ctx = Recorder::new(width, height);
+        state.render_main_menu(&mut ctx);
+        assert!(ctx.row(3).contains("Welcome to Flappy Dragon"));
+
+        let mut ctx = Recorder::new(width, height);
+        state.render_dead(&mut ctx);
+        assert!(ctx.row(5).contains("You are dead!"));
+    }
+
+    // a frame that took a whole minute, like after dragging the window,
+    // steps no more physics frames than the config allows
+    #[test]
+    fn a_long_stall_steps_at_most_the_configured_frames() {
+        for &max_steps in &[1, 2, 5] {
+            let mut state = open_sky();
+            state.config.max_steps_per_frame = max_steps;
+            state.update(frame(60_000.0));
+            assert!(matches!(state.mode, GameMode::Playing));
+            assert_eq!(state.player.x, START_X + i32::try_from(max_steps).unwrap());
+            // and the rest of the minute is dropped, not saved up for later
+            assert!(state.frame_time < state.config.frame_duration);
+            state.update(frame(0.0));
+            assert_eq!(state.player.x, START_X + i32::try_from(max_steps).unwrap());
+        }
+    }
+
+    // where the obstacles of a run come in while the player flies 200
+    // columns, one spawn check a column
+    fn spawned_xs(config: GameConfig) -> Vec<i32> {
+        let mut state = State::in_memory(config, Settings::default(), 1);
+        state.audio.set_muted(true);
+        state.restart();
+        for _ in 0..200 {
+            state.player.x += 1;
+            state.spawn();
+        }
+        state.obstacles.iter().map(|obstacle| obstacle.x).collect()
+    }
+
+    // the first obstacle at the right edge of the screen,
+    // every next one obstacle_spacing columns further on
+    #[test]
+    fn obstacles_spawn_obstacle_spacing_apart() {
+        let configs = [
+            GameConfig::new(Screen::default(), Difficulty::Easy),
+            GameConfig::new(Screen::default(), Difficulty::Hard),
+            GameConfig {
+                obstacle_spacing: 13,
+                ..GameConfig::default()
+            },
+        ];
+        for config in configs {
+            let xs = spawned_xs(config);
+            let last = START_X + 200 + config.screen.width;
+            let spacing = usize::try_from(config.obstacle_spacing).unwrap();
+            let expected: Vec<_> = (config.screen.width..=last).step_by(spacing).collect();
+            assert_eq!(xs, expected, "spacing {}", config.obstacle_spacing);
+        }
+    }
+
+    // whatever the settings say, everyone flies the same daily course
+    #[test]
+    fn the_daily_challenge_is_the_same_on_every_difficulty() {
+        let daily = |difficulty| {
+            let mut state = quiet_state();
+            state.settings.difficulty = difficulty;
+            state.start(RunKind::Daily);
+            for _ in 0..200 {
+                state.player.x += 1;
+                state.spawn();
+            }
+            assert_eq!(state.lives, DAILY_DIFFICULTY.lives());
+            assert_eq!(state.recording.difficulty, DAILY_DIFFICULTY);
+            (
+                state.seed,
+                format!("{:?} {:?}", state.obstacles, state.config),
+            )
+        };
+        let easy = daily(Difficulty::Easy);
+        assert_eq!(daily(Difficulty::Hard), easy);
+        assert_eq!(daily(Difficulty::Normal), easy);
+    }
+}
@@ -0,0 +1,659 @@
+use crate::{
+    config::FRAME_DURATION,
+    course::CourseObstacle,
+    draw, glyph,
+    player::Player,
+    renderer::{Renderable, Renderer},
+    theme::Theme,
+    update::Update,
+    Difficulty,
+};
+use bracket_lib::prelude::*;
+
+// one in this many obstacles has a gap that swings up and down
+const MOVING_GAP_CHANCE: i32 = 4;
+
+// rows a moving gap swings away from where it spawned
+const MOVING_GAP_RANGE: f32 = 6.0;
+
+// columns an obstacle covers, starting at its x
+// pipes and spikes are both a single column
+const WIDTH: i32 = 1;
+
+// columns a boss covers, it gives the player longer to get through
+const BOSS_WIDTH: i32 = 3;
+
+// rows narrower a boss's gap is than a pipe's would be at the same score
+const BOSS_GAP_SHRINK: i32 = 4;
+
+// how fast a boss's gap swings, radians per FRAME_DURATION
+const BOSS_SWING_SPEED: f32 = 0.1;
+
+// percent chance of spikes gained per point of score
+const SPIKE_CHANCE_PER_POINT: i32 = 2;
+
+// highest percent chance of an obstacle being spikes
+const MAX_SPIKE_CHANCE: i32 = 40;
+
+// score double-gap obstacles start showing up at
+const DOUBLE_GAP_SCORE: i32 = 15;
+
+// one in this many pipes has two gaps once the score is high enough
+const DOUBLE_GAP_CHANCE: i32 = 4;
+
+// what an obstacle looks like and how it can be hit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObstacleKind {
+    // a pair of pipes with a gap between them
+    Pipe,
+    // a stack of spikes covering rows top..bottom, growing out of
+    // the ceiling or the floor, the rest of the column is free
+    Spikes {
+        from_ceiling: bool,
+        top: i32,
+        bottom: i32,
+    },
+    // pipes with a gap above the middle of the screen and another
+    // below it, gap_y is the upper one, both are the same size
+    DoubleGap {
+        lower_gap_y: i32,
+    },
+    // a wide pair of pipes with a narrow gap that always swings,
+    // the milestone at every few points
+    Boss,
+}
+
+#[derive(Debug)]
+pub struct Obstacle {
+    // pipe or spikes
+    pub kind: ObstacleKind,
+    // world-space to match the player’s world-space x value
+    pub x: i32,
+    // obstacle center position
+    pub gap_y: i32,
+    // the maximum (obtained via i32::max) of 20 minus the player’s score
+    // increasing game difficulty
+    pub size: i32,
+    // set once the player flies past, so each obstacle scores only once
+    pub passed: bool,
+    // where the gap spawned, a moving gap swings around this row
+    pub base_gap_y: i32,
+    // how fast the gap swings, radians per FRAME_DURATION, 0.0 keeps it still
+    pub vy: f32,
+    // current position in the swing
+    pub phase: f32,
+    // rows of the screen it spawned on, a swinging gap stays inside them
+    pub screen_height: i32,
+}
+
+impl Obstacle {
+    // spikes get more likely the higher the score, pipes otherwise,
+    // some of them with two gaps at higher scores
+    // a pipe's gap starts at base_gap and shrinks with the score
+    pub fn new(
+        x: i32,
+        score: i32,
+        base_gap: i32,
+        screen_height: i32,
+        random: &mut RandomNumberGenerator,
+    ) -> Self {
+        let spike_chance = i32::min(MAX_SPIKE_CHANCE, score * SPIKE_CHANCE_PER_POINT);
+        if random.range(0, 100) < spike_chance {
+            return Obstacle::spikes(random, x, screen_height);
+        }
+        if score >= DOUBLE_GAP_SCORE && random.range(0, DOUBLE_GAP_CHANCE) == 0 {
+            return Obstacle::double_gap(random, x, score, base_gap, screen_height);
+        }
+
+        // never more than half the screen, so on a short screen the gap
+        // still fits and a swinging gap has somewhere to swing
+        let size = i32::max(2, base_gap - score).min(screen_height / 2);
+        // keep a fifth of the screen clear above and below the gap center,
+        // and all of a big gap on the screen
+        let gap_y = clamp_gap(
+            random.range(screen_height / 5, screen_height - screen_height / 5),
+            size,
+            screen_height,
+        );
+        // only some obstacles move, the rest keep a static gap
+        let vy = if random.range(0, MOVING_GAP_CHANCE) == 0 {
+            random.range(0.05, 0.15)
+        } else {
+            0.0
+        };
+        Obstacle {
+            kind: ObstacleKind::Pipe,
+            // world-space: x coordination
+            x,
+            // gap center y position
+            gap_y,
+            //gap size. smaller when player winning more score
+            size,
+            // not passed yet
+            passed: false,
+            base_gap_y: gap_y,
+            vy,
+            // start in a random spot of the swing
+            phase: random.range(0.0, std::f32::consts::TAU),
+            screen_height,
+        }
+    }
+
+    // a still pipe from a handcrafted course,
+    // moved back onto the screen if the course asks for too much
+    #[must_use]
+    pub fn planned(x: i32, planned: CourseObstacle, screen_height: i32) -> Self {
+        let size = planned.size.clamp(2, screen_height - 2);
+        let gap_y = clamp_gap(planned.gap_y, size, screen_height);
+        Obstacle {
+            kind: ObstacleKind::Pipe,
+            x,
+            gap_y,
+            size,
+            passed: false,
+            base_gap_y: gap_y,
+            vy: 0.0,
+            phase: 0.0,
+            screen_height,
+        }
+    }
+
+    // a wide, still gap in the middle of the screen for the tutorial,
+    // capped like a pipe's so a short screen still has walls
+    #[must_use]
+    pub fn tutorial(x: i32, screen_height: i32) -> Self {
+        let size = Difficulty::Easy.base_gap().min(screen_height / 2);
+        let gap_y = clamp_gap(screen_height / 2, size, screen_height);
+        Obstacle {
+            kind: ObstacleKind::Pipe,
+            x,
+            gap_y,
+            size,
+            passed: false,
+            base_gap_y: gap_y,
+            vy: 0.0,
+            phase: 0.0,
+            screen_height,
+        }
+    }
+
+    // a boss at x, its gap narrower than a pipe's at the same score
+    pub fn boss(
+        x: i32,
+        score: i32,
+        base_gap: i32,
+        screen_height: i32,
+        random: &mut RandomNumberGenerator,
+    ) -> Self {
+        // capped like a pipe's, a boss at a low score on a short
+        // screen would otherwise have a gap taller than the screen
+        let size = i32::max(2, base_gap - score - BOSS_GAP_SHRINK).min(screen_height / 2);
+        let gap_y = clamp_gap(
+            random.range(screen_height / 3, screen_height - screen_height / 3),
+            size,
+            screen_height,
+        );
+        Obstacle {
+            kind: ObstacleKind::Boss,
+            x,
+            gap_y,
+            size,
+            passed: false,
+            base_gap_y: gap_y,
+            vy: BOSS_SWING_SPEED,
+            phase: 0.0,
+            screen_height,
+        }
+    }
+
+    // one gap in each half of the screen with solid pipe between them,
+    // the gaps are kept to a quarter of the screen so both fit
+    fn double_gap(
+        random: &mut RandomNumberGenerator,
+        x: i32,
+        score: i32,
+        base_gap: i32,
+        screen_height: i32,
+    ) -> Self {
+        let size = i32::max(2, base_gap - score).min(screen_height / 4);
+        let half_size = size / 2;
+        let middle = screen_height / 2;
+        let gap_y = random.range(screen_height / 5, middle - half_size);
+        let lower_gap_y = random.range(middle + half_size + 1, screen_height - screen_height / 5);
+        Obstacle {
+            kind: ObstacleKind::DoubleGap { lower_gap_y },
+            x,
+            gap_y,
+            size,
+            passed: false,
+            base_gap_y: gap_y,
+            // the gaps never move
+            vy: 0.0,
+            phase: 0.0,
+            screen_height,
+        }
+    }
+
+    // rows from y to the middle of the nearest gap
+    #[must_use]
+    pub fn gap_distance(&self, y: i32) -> i32 {
+        let distance = (y - self.gap_y).abs();
+        match self.kind {
+            ObstacleKind::DoubleGap { lower_gap_y } => distance.min((y - lower_gap_y).abs()),
+            _ => distance,
+        }
+    }
+
+    // last world-space column the obstacle covers
+    #[must_use]
+    pub fn right_x(&self) -> i32 {
+        let width = if self.kind == ObstacleKind::Boss {
+            BOSS_WIDTH
+        } else {
+            WIDTH
+        };
+        self.x + width - 1
+    }
+
+    // spikes reaching a quarter to half of the way across the screen
+    // gap_y and size describe the free part of the column,
+    // so coins and close passes work the same as with pipes
+    fn spikes(random: &mut RandomNumberGenerator, x: i32, screen_height: i32) -> Self {
+        let length = random.range(screen_height / 4, screen_height / 2);
+        let from_ceiling = random.range(0, 2) == 0;
+        let (top, bottom) = if from_ceiling {
+            (0, length)
+        } else {
+            (screen_height - length, screen_height)
+        };
+        let free_top = if from_ceiling { length } else { 0 };
+        let gap_y = free_top + (screen_height - length) / 2;
+        Obstacle {
+            kind: ObstacleKind::Spikes {
+                from_ceiling,
+                top,
+                bottom,
+            },
+            x,
+            gap_y,
+            size: screen_height - length,
+            passed: false,
+            base_gap_y: gap_y,
+            // spikes never move
+            vy: 0.0,
+            phase: 0.0,
+            screen_height,
+        }
+    }
+
+    // check if player hit obstacle
+    // true: game over
+    // from_x is where the player was before this physics step,
+    // the check runs after every step so the row is the one the player
+    // was in while crossing the column, not where a later step took it
+    // the cases that matter when changing the gap logic:
+    // - only columns entered this step count, from_x + 1 ..= player.x,
+    //   from_x itself was already checked the step before
+    // - a step that doesn't move the player can't hit anything new
+    // - the gap is gap_y - half_size ..= gap_y + half_size, both edge rows are safe
+    // - one row above or below that range is a hit
+    // spikes only hit inside the rows they cover, top..bottom
+    // a boss is hit in any of its columns, x ..= right_x
+    #[must_use]
+    pub fn hit_obstacle(&self, player: &Player, from_x: i32) -> bool {
+        let does_x_match = from_x < self.right_x() && player.x >= self.x; // (1)
+        if let ObstacleKind::Spikes { top, bottom, .. } = self.kind {
+            return does_x_match && player.y >= top && player.y < bottom;
+        }
+
+        let half_size = self.size / 2;
+        // either gap is safe, with the same edge rows as a single one
+        if let ObstacleKind::DoubleGap { lower_gap_y } = self.kind {
+            let in_gap = |gap_y: i32| (gap_y - half_size..=gap_y + half_size).contains(&player.y);
+            return does_x_match && !in_gap(self.gap_y) && !in_gap(lower_gap_y);
+        }
+
+        let player_above_gap = player.y < self.gap_y - half_size; // (2)
+        let player_below_gap = player.y > self.gap_y + half_size;
+
+        // the player crossed the obstacle's column
+        does_x_match && (player_above_gap || player_below_gap) // (3)
+    }
+}
+
+// the middle row closest to gap_y that keeps a gap of `size` rows,
+// both edge rows included, on a screen `screen_height` rows tall
+fn clamp_gap(gap_y: i32, size: i32, screen_height: i32) -> i32 {
+    let half_size = size / 2;
+    gap_y.clamp(half_size, screen_height - 1 - half_size)
+}
+
+impl Update for Obstacle {
+    // move a swinging gap, the swing is tuned per FRAME_DURATION like
+    // the player's physics, so it keeps pace with the player when the
+    // physics frames get shorter
+    // the gap is clamped so it never leaves the playfield
+    #[allow(clippy::cast_possible_truncation)]
+    fn update(&mut self, dt: f32) {
+        if self.vy == 0.0 {
+            return;
+        }
+        self.phase += self.vy * dt * 1000.0 / FRAME_DURATION;
+        let swing = (self.phase.sin() * MOVING_GAP_RANGE) as i32;
+        self.gap_y = clamp_gap(self.base_gap_y + swing, self.size, self.screen_height);
+    }
+}
+
+impl Renderable for Obstacle {
+    // an obstacle runs from the top of the console to the bottom
+    fn render<R: Renderer>(&self, ctx: &mut R, player_x: i32, theme: &Theme) {
+        let screen_height = ctx.size().1;
+        let screen_x = self.x - player_x;
+
+        if let ObstacleKind::Spikes {
+            from_ceiling,
+            top,
+            bottom,
+        } = self.kind
+        {
+            // points away from whatever the spikes grow out of
+            let glyph = if from_ceiling {
+                glyph::SPIKES_DOWN
+            } else {
+                glyph::SPIKES_UP
+            };
+            for y in top..bottom {
+                draw::set(ctx, screen_x, y, theme.spikes, BLACK, glyph);
+            }
+            return;
+        }
+
+        let half_size = self.size / 2;
+        if let ObstacleKind::DoubleGap { lower_gap_y } = self.kind {
+            // above the upper gap, between the gaps, below the lower gap
+            let solid = (0..self.gap_y - half_size)
+                .chain(self.gap_y + half_size..lower_gap_y - half_size)
+                .chain(lower_gap_y + half_size..screen_height);
+            for y in solid {
+                draw::set(ctx, screen_x, y, theme.obstacle, BLACK, glyph::WALL);
+            }
+            return;
+        }
+
+        let (color, glyph) = if self.kind == ObstacleKind::Boss {
+            (theme.boss, glyph::BOSS_WALL)
+        } else {
+            (theme.obstacle, glyph::WALL)
+        };
+
+        for x in screen_x..=self.right_x() - player_x {
+            // Draw the top half of the obstacle
+            for y in 0..self.gap_y - half_size {
+                draw::set(ctx, x, y, color, BLACK, glyph);
+            }
+
+            // Draw the bottom half of the obstacle
+            for y in self.gap_y + half_size..screen_height {
+                draw::set(ctx, x, y, color, BLACK, glyph);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::MIN_HEIGHT,
+        config::{GameConfig, Screen},
+        player::PhysicsConfig,
+    };
+
+    // screen the obstacles of these tests are built for
+    const HEIGHT: i32 = 50;
+
+    // column of the obstacles of these tests
+    const X: i32 = 10;
+
+    fn player_at(x: i32, y: i32) -> Player {
+        Player::new(x, y, &PhysicsConfig::default())
+    }
+
+    // a still pipe at X with the gap rows 23 ..= 27
+    fn pipe() -> Obstacle {
+        Obstacle::planned(X, CourseObstacle { gap_y: 25, size: 4 }, HEIGHT)
+    }
+
+    // spikes at X covering rows top..bottom, the rest of the column is free
+    fn spikes(from_ceiling: bool, top: i32, bottom: i32) -> Obstacle {
+        let free_top = if from_ceiling { bottom } else { 0 };
+        let size = HEIGHT - (bottom - top);
+        Obstacle {
+            kind: ObstacleKind::Spikes {
+                from_ceiling,
+                top,
+                bottom,
+            },
+            x: X,
+            gap_y: free_top + size / 2,
+            size,
+            passed: false,
+            base_gap_y: free_top + size / 2,
+            vy: 0.0,
+            phase: 0.0,
+            screen_height: HEIGHT,
+        }
+    }
+
+    // two gaps at X, rows 13 ..= 17 and 33 ..= 37
+    fn double_gap() -> Obstacle {
+        Obstacle {
+            kind: ObstacleKind::DoubleGap { lower_gap_y: 35 },
+            gap_y: 15,
+            size: 4,
+            ..pipe()
+        }
+    }
+
+    // whether a player that has just moved from the column before x
+    // into row y of column x hits the obstacle
+    fn hits(obstacle: &Obstacle, x: i32, y: i32) -> bool {
+        obstacle.hit_obstacle(&player_at(x, y), x - 1)
+    }
+
+    #[test]
+    fn the_middle_of_the_gap_is_safe() {
+        assert!(!hits(&pipe(), X, 25));
+    }
+
+    #[test]
+    fn both_edge_rows_of_the_gap_are_safe() {
+        assert!(!hits(&pipe(), X, 23));
+        assert!(!hits(&pipe(), X, 27));
+    }
+
+    #[test]
+    fn one_row_outside_the_gap_is_a_hit() {
+        assert!(hits(&pipe(), X, 22));
+        assert!(hits(&pipe(), X, 28));
+    }
+
+    // nowhere near the gap, but not in the column either
+    #[test]
+    fn only_the_obstacles_column_can_hit() {
+        assert!(!hits(&pipe(), X - 1, 0));
+        assert!(!hits(&pipe(), X + 1, 0));
+    }
+
+    // still in the column, but it was checked on the step that entered it
+    #[test]
+    fn a_step_that_stays_in_the_column_hits_nothing_new() {
+        assert!(!pipe().hit_obstacle(&player_at(X, 0), X));
+    }
+
+    #[test]
+    fn spikes_from_the_floor_hit_every_row_they_cover() {
+        let spikes = spikes(false, 30, HEIGHT);
+        assert!(hits(&spikes, X, 30));
+        assert!(hits(&spikes, X, HEIGHT - 1));
+        assert!(!hits(&spikes, X, 29));
+    }
+
+    #[test]
+    fn spikes_from_the_ceiling_hit_every_row_they_cover() {
+        let spikes = spikes(true, 0, 20);
+        assert!(hits(&spikes, X, 0));
+        assert!(hits(&spikes, X, 19));
+        assert!(!hits(&spikes, X, 20));
+    }
+
+    // a pipe is solid on both sides of its gap, spikes only on one
+    #[test]
+    fn only_a_pipe_is_hit_on_both_sides() {
+        let (pipe, spikes) = (pipe(), spikes(false, 30, HEIGHT));
+        assert!(hits(&pipe, X, 1));
+        assert!(!hits(&spikes, X, 1));
+        assert!(hits(&pipe, X, 40));
+        assert!(hits(&spikes, X, 40));
+    }
+
+    // whichever end they grow from, generated spikes touch it and
+    // leave at least half the column free to fly through
+    #[test]
+    fn generated_spikes_leave_room_to_fly() {
+        for seed in 0..200 {
+            let mut random = RandomNumberGenerator::seeded(seed);
+            let spikes = Obstacle::spikes(&mut random, X, HEIGHT);
+            let ObstacleKind::Spikes {
+                from_ceiling,
+                top,
+                bottom,
+            } = spikes.kind
+            else {
+                panic!("spikes came out as {:?}", spikes.kind);
+            };
+            let touches_its_end = if from_ceiling {
+                top == 0
+            } else {
+                bottom == HEIGHT
+            };
+            assert!(touches_its_end, "seed {}: {}..{}", seed, top, bottom);
+            assert!(
+                bottom - top <= HEIGHT / 2,
+                "seed {}: {}..{}",
+                seed,
+                top,
+                bottom
+            );
+            assert!(!hits(&spikes, X, spikes.gap_y), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn either_gap_of_a_double_gap_is_safe() {
+        for y in (13..=17).chain(33..=37) {
+            assert!(!hits(&double_gap(), X, y), "row {}", y);
+        }
+    }
+
+    // above the upper gap, the solid middle and below the lower gap
+    #[test]
+    fn the_solid_parts_of_a_double_gap_hit() {
+        for y in [0, 12, 18, 25, 32, 38, HEIGHT - 1].iter().copied() {
+            assert!(hits(&double_gap(), X, y), "row {}", y);
+        }
+    }
+
+    // however the gaps come out there is solid pipe between them
+    #[test]
+    fn generated_double_gaps_keep_a_solid_middle() {
+        for seed in 0..200 {
+            let mut random = RandomNumberGenerator::seeded(seed);
+            let obstacle = Obstacle::double_gap(&mut random, X, 20, 20, HEIGHT);
+            let ObstacleKind::DoubleGap { lower_gap_y } = obstacle.kind else {
+                panic!("a double gap came out as {:?}", obstacle.kind);
+            };
+            let half_size = obstacle.size / 2;
+            assert!(
+                obstacle.gap_y + half_size + 1 < lower_gap_y - half_size,
+                "seed {}",
+                seed
+            );
+            assert!(!hits(&obstacle, X, obstacle.gap_y), "seed {}", seed);
+            assert!(!hits(&obstacle, X, lower_gap_y), "seed {}", seed);
+        }
+    }
+
+    // the gap sizes runs start from, every difficulty and hardcore
+    fn base_gaps() -> Vec<i32> {
+        let screen = Screen::default();
+        let mut hardcore = GameConfig::default();
+        hardcore.set_hardcore();
+        [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+            .iter()
+            .map(|&difficulty| GameConfig::new(screen, difficulty).base_gap)
+            .chain(std::iter::once(hardcore.base_gap))
+            .collect()
+    }
+
+    // what is wrong with where an obstacle left room to fly, if anything:
+    // every gap has to have at least two rows, all of them on the screen, so
+    // the walls above and below run from the edge of the screen to the gap,
+    // and it can't take up the whole column or there are no walls at all
+    // spikes have to leave some of the column free
+    fn gap_problem(obstacle: &Obstacle) -> Option<String> {
+        let height = obstacle.screen_height;
+        let gaps = match obstacle.kind {
+            ObstacleKind::Spikes { top, bottom, .. } => {
+                let fits = top >= 0 && bottom <= height && bottom - top < height;
+                return (!fits).then(|| format!("spikes over {top}..{bottom}"));
+            }
+            ObstacleKind::DoubleGap { lower_gap_y } => vec![obstacle.gap_y, lower_gap_y],
+            ObstacleKind::Pipe | ObstacleKind::Boss => vec![obstacle.gap_y],
+        };
+        if obstacle.size < 2 {
+            return Some(format!("a gap of {} rows", obstacle.size));
+        }
+        let half_size = obstacle.size / 2;
+        gaps.into_iter()
+            .find(|&gap_y| {
+                let (top, bottom) = (gap_y - half_size, gap_y + half_size);
+                top < 0 || bottom >= height || (top == 0 && bottom == height - 1)
+            })
+            .map(|gap_y| format!("the gap {gap_y} +- {half_size}"))
+    }
+
+    // every obstacle that can spawn, for any score, difficulty and screen
+    // height the game allows, and wherever a swinging gap goes in its swing
+    // each case has a seed of its own, printed with a failure to reproduce it
+    #[test]
+    #[allow(clippy::cast_sign_loss)]
+    fn gaps_always_stay_on_the_screen() {
+        let scores = (0..=60).chain([100, 1000, 100_000, i32::MAX / 2].iter().copied());
+        for score in scores {
+            for height in MIN_HEIGHT..=120 {
+                for base_gap in base_gaps() {
+                    let seed = (height as u64) << 48 | (base_gap as u64) << 32 | score as u64;
+                    let mut random = RandomNumberGenerator::seeded(seed);
+                    let obstacles = [
+                        Obstacle::new(X, score, base_gap, height, &mut random),
+                        Obstacle::boss(X, score, base_gap, height, &mut random),
+                        Obstacle::tutorial(X, height),
+                    ];
+                    for mut obstacle in obstacles {
+                        for frame in 0..100 {
+                            if let Some(problem) = gap_problem(&obstacle) {
+                                panic!(
+                                    "seed {}, score {}, height {}, base gap {}, {:?} \
+                                     after {} frames: {}",
+                                    seed, score, height, base_gap, obstacle.kind, frame, problem
+                                );
+                            }
+                            obstacle.update(FRAME_DURATION / 1000.0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
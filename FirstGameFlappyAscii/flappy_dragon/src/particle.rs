@@ -0,0 +1,82 @@
+use crate::{
+    draw, glyph,
+    renderer::{Renderable, Renderer},
+    theme::Theme,
+    update::Update,
+};
+use bracket_lib::prelude::*;
+
+// a single spark of the explosion when the player crashes
+#[derive(Debug)]
+pub struct Particle {
+    // world-space position, like obstacles, but not snapped to a cell
+    x: f32,
+    y: f32,
+    // cells per second
+    vx: f32,
+    vy: f32,
+    // seconds left before it disappears
+    life: f32,
+    // seconds it lived for in total
+    lifetime: f32,
+}
+
+impl Particle {
+    // a spark moving away from x, y in a random direction
+    // sparks are thrown forwards so they stay on screen in front of the player
+    pub fn new(random: &mut RandomNumberGenerator, x: f32, y: f32) -> Self {
+        let lifetime = random.range(0.3, 0.5);
+        Particle {
+            x,
+            y,
+            vx: random.range(2.0, 15.0),
+            vy: random.range(-10.0, 10.0),
+            life: lifetime,
+            lifetime,
+        }
+    }
+
+    // a burst of `count` sparks from the same spot
+    pub fn burst(
+        random: &mut RandomNumberGenerator,
+        x: f32,
+        y: f32,
+        count: usize,
+    ) -> Vec<Particle> {
+        (0..count).map(|_| Particle::new(random, x, y)).collect()
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.life > 0.0
+    }
+}
+
+impl Update for Particle {
+    // advance by dt real seconds
+    fn update(&mut self, dt: f32) {
+        self.x += self.vx * dt;
+        self.y += self.vy * dt;
+        self.life -= dt;
+    }
+}
+
+impl Renderable for Particle {
+    // cools down from the player's color to the obstacles' color
+    // and shrinks from '*' to '.' as it dies
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn render<R: Renderer>(&self, ctx: &mut R, player_x: i32, theme: &Theme) {
+        if !self.is_alive() {
+            return;
+        }
+        let age = 1.0 - self.life / self.lifetime;
+        let color = RGB::named(theme.player).lerp(RGB::named(theme.obstacle), age);
+        let glyph = if age < 0.5 {
+            glyph::SPARK
+        } else {
+            glyph::EMBER
+        };
+        let screen_x = (self.x - player_x as f32).round() as i32;
+        let screen_y = self.y.round() as i32;
+        draw::set(ctx, screen_x, screen_y, color, BLACK, glyph);
+    }
+}
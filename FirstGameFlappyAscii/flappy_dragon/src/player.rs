@@ -0,0 +1,457 @@
+use crate::{config::FRAME_DURATION, draw, glyph, renderer::Renderer, theme::Theme};
+use bracket_lib::prelude::*;
+
+// physics frames spent falling before the flaps are given back
+const FLAP_RESET_FRAMES: i32 = 3;
+
+// physics frames the player flashes (and can't be hit) after the shield breaks
+const SHIELD_FLASH_FRAMES: i32 = 10;
+
+// physics frames the player flashes (and can't be hit) after losing a life
+const RESPAWN_FLASH_FRAMES: i32 = 20;
+
+// physics frames the wings-up glyph is shown after a flap
+const FLAP_ANIM_FRAMES: i32 = 3;
+
+// physics frames after a flap that holding the flap key keeps lifting for
+const FLAP_HOLD_FRAMES: i32 = 3;
+
+// seconds a flap that couldn't lift the player is kept around for,
+// it then goes off at the first physics frame that has a flap to give
+// tunable: longer forgives earlier presses, too long and flaps fire late
+const FLAP_BUFFER_SECONDS: f32 = 0.1;
+
+// how the player moves, per FRAME_DURATION physics frame
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicsConfig {
+    // velocity added by gravity every frame
+    pub gravity: f32,
+    // fastest the player can fall, in rows per frame
+    pub max_velocity: f32,
+    // velocity a tap of the flap key sets, negative is upwards
+    // the weakest a flap can be
+    pub flap_impulse: f32,
+    // fastest upwards velocity holding the flap key can build up to
+    // the strongest a flap can be
+    pub max_flap_impulse: f32,
+    // velocity taken off every physics frame the flap key is held after a flap
+    pub flap_hold_lift: f32,
+    // physics frames after starting to fall that a flap still goes
+    // through at full strength with none left, 0 turns it off
+    pub coyote_frames: i32,
+    // how many flaps the player gets before having to fall again
+    // 1 = single jump, 2 = double jump, 0 = no limit
+    pub max_flaps: u8,
+}
+
+impl Default for PhysicsConfig {
+    // the feel of the Normal difficulty
+    fn default() -> Self {
+        PhysicsConfig {
+            gravity: 0.1,
+            max_velocity: 2.0,
+            flap_impulse: -2.0,
+            max_flap_impulse: -3.0,
+            flap_hold_lift: 0.4,
+            coyote_frames: 2,
+            max_flaps: 2,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Player {
+    // x position(line position)
+    // default: 0
+    pub x: i32,
+    //y position (vertical position)
+    // the cell exact_y is in, for collisions and everything else
+    // that works in whole rows, only ever set through move_to
+    pub y: i32,
+    // where the player really is, physics moves it by fractions of a row
+    pub exact_y: f32,
+    // exact_y before the last physics frame, drawing blends from it to exact_y
+    pub prev_y: f32,
+    //vertical velocity
+    pub velocity: f32,
+    // flaps left before the player has to fall again
+    pub flaps_remaining: u8,
+    // flap limit, 0 means unlimited
+    pub max_flaps: u8,
+    // consecutive physics frames with a positive (falling) velocity
+    pub falling_frames: i32,
+    // a shield absorbs the next crash
+    pub shielded: bool,
+    // physics frames left flashing after losing the shield
+    pub flash_timer: i32,
+    // physics frames left showing the flap glyph
+    pub anim_timer: i32,
+    // whether the flap key is down, set before every physics update
+    pub flap_held: bool,
+    // physics frames left in which holding the flap key keeps lifting
+    pub hold_frames: i32,
+    // seconds left to use a flap pressed while none was available
+    pub buffered_flap: f32,
+    // physics frames left in which a flap works even with none left
+    pub coyote_timer: i32,
+    // 1.0 when gravity pulls down, -1.0 while it is flipped and pulls up
+    pub gravity_dir: f32,
+}
+
+impl Player {
+    //player constructor to initialize instance
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(x: i32, y: i32, physics: &PhysicsConfig) -> Self {
+        Player {
+            // x position of player: a world-space positon
+            x,
+            // y: vertical position of player in screen
+            y,
+            exact_y: y as f32,
+            prev_y: y as f32,
+            //velocity: player's vertical velocity
+            velocity: 0.0,
+            // start with a full set of flaps
+            flaps_remaining: physics.max_flaps,
+            max_flaps: physics.max_flaps,
+            // not falling yet
+            falling_frames: 0,
+            // no shield until one is collected
+            shielded: false,
+            flash_timer: 0,
+            // resting glyph
+            anim_timer: 0,
+            flap_held: false,
+            hold_frames: 0,
+            buffered_flap: 0.0,
+            coyote_timer: 0,
+            gravity_dir: 1.0,
+        }
+    }
+
+    // put the player at exact row y, and in the cell it rounds to
+    #[allow(clippy::cast_possible_truncation)]
+    fn move_to(&mut self, y: f32) {
+        self.exact_y = y;
+        self.y = y.round() as i32;
+    }
+
+    // advance the player by dt seconds
+    // gravity and velocity are tuned per FRAME_DURATION, so they are
+    // scaled by how much of such a frame dt covers
+    // wind is added on top of gravity, negative blows upwards
+    pub fn gravity_and_move(&mut self, dt: f32, physics: &PhysicsConfig, wind: f32) {
+        let scale = dt * 1000.0 / FRAME_DURATION;
+        self.prev_y = self.exact_y;
+        // worked out as if gravity pulled down, positive is falling,
+        // and turned the right way round again once done
+        let mut velocity = self.velocity * self.gravity_dir;
+
+        // Increment gravity, never falling faster than the cap
+        if velocity < physics.max_velocity {
+            velocity = f32::min(
+                physics.max_velocity,
+                velocity + (physics.gravity + wind * self.gravity_dir) * scale,
+            );
+        }
+
+        // a held flap keeps lifting for a little while,
+        // letting go of the key ends it for good
+        if self.flap_held && self.hold_frames > 0 {
+            self.hold_frames -= 1;
+            velocity = f32::max(
+                physics.max_flap_impulse,
+                velocity - physics.flap_hold_lift * scale,
+            );
+        } else {
+            self.hold_frames = 0;
+        }
+        self.velocity = velocity * self.gravity_dir;
+
+        // Apply gravity
+        // the player is kept on screen, touching the ceiling is a crash
+        // that play() checks for, just like falling off the bottom,
+        // whichever way gravity pulls
+        self.move_to(f32::max(0.0, self.exact_y + self.velocity * scale));
+
+        // Move the player
+        self.x += 1;
+
+        // count down the flashing after a shield break
+        if self.flash_timer > 0 {
+            self.flash_timer -= 1;
+        }
+
+        // fold the wings back after a flap
+        if self.anim_timer > 0 {
+            self.anim_timer -= 1;
+        }
+
+        // give the flaps back after falling for a little while,
+        // a flap just before that is let through on coyote time
+        if self.coyote_timer > 0 {
+            self.coyote_timer -= 1;
+        }
+        if velocity > 0.0 {
+            self.falling_frames += 1;
+            if self.falling_frames == 1 {
+                self.coyote_timer = physics.coyote_frames;
+            }
+            if self.falling_frames >= FLAP_RESET_FRAMES {
+                self.flaps_remaining = self.max_flaps;
+            }
+        } else {
+            self.falling_frames = 0;
+        }
+    }
+
+    // returns true when the flap actually lifted the player
+    pub fn flap(&mut self, physics: &PhysicsConfig) -> bool {
+        // refuse to lift once the flaps are used up,
+        // unless the player has only just started falling
+        if self.max_flaps > 0 {
+            if self.flaps_remaining > 0 {
+                self.flaps_remaining -= 1;
+            } else if self.coyote_timer > 0 {
+                self.coyote_timer = 0;
+            } else {
+                return false;
+            }
+        }
+        // a flap always works against gravity
+        self.velocity = physics.flap_impulse * self.gravity_dir;
+        self.anim_timer = FLAP_ANIM_FRAMES;
+        self.hold_frames = FLAP_HOLD_FRAMES;
+        true
+    }
+
+    // flap for a key press, remembering it for a little while when the
+    // flaps are used up so a press just before they come back still counts
+    // returns true when the flap lifted the player straight away
+    pub fn press_flap(&mut self, physics: &PhysicsConfig) -> bool {
+        let flapped = self.flap(physics);
+        self.buffered_flap = if flapped { 0.0 } else { FLAP_BUFFER_SECONDS };
+        flapped
+    }
+
+    // try the remembered flap, once per physics frame after moving
+    // returns true when it lifted the player
+    pub fn apply_buffered_flap(&mut self, physics: &PhysicsConfig) -> bool {
+        if self.buffered_flap > 0.0 && self.flap(physics) {
+            self.buffered_flap = 0.0;
+            return true;
+        }
+        false
+    }
+
+    // forget the remembered flap once dt real seconds took it out of the window
+    pub fn expire_flap_buffer(&mut self, dt: f32) {
+        self.buffered_flap = f32::max(0.0, self.buffered_flap - dt);
+    }
+
+    // use up the shield instead of crashing
+    pub fn absorb_hit(&mut self, ground_y: i32) {
+        self.shielded = false;
+        self.flash_timer = SHIELD_FLASH_FRAMES;
+        self.keep_on_screen(ground_y);
+    }
+
+    // bounce back up if the player has fallen into the ground,
+    // ground_y is its top row
+    #[allow(clippy::cast_precision_loss)]
+    pub fn keep_on_screen(&mut self, ground_y: i32) {
+        if self.y >= ground_y {
+            self.move_to((ground_y - 1) as f32);
+            self.velocity = -2.0;
+        }
+        // and drop back down off the ceiling
+        if self.y <= 0 {
+            self.move_to(1.0);
+            self.velocity = 0.0;
+        }
+        // a jump, not something to draw the in-between of
+        self.prev_y = self.exact_y;
+    }
+
+    // start over at row y after losing a life, flashing for a while
+    #[allow(clippy::cast_precision_loss)]
+    pub fn respawn(&mut self, y: i32) {
+        self.move_to(y as f32);
+        self.prev_y = self.exact_y;
+        self.velocity = 0.0;
+        self.flaps_remaining = self.max_flaps;
+        self.flash_timer = RESPAWN_FLASH_FRAMES;
+        self.buffered_flap = 0.0;
+        self.hold_frames = 0;
+        self.coyote_timer = 0;
+    }
+
+    // nothing can hit the player while it is flashing
+    #[must_use]
+    pub fn is_invulnerable(&self) -> bool {
+        self.flash_timer > 0
+    }
+
+    // drawn `camera_x` columns into the world, in `color` unless flashing or shielded
+    // `blend` is how far into the next physics frame the real time is, 0.0..1.0,
+    // the player is drawn that far between prev_y and y
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn render<R: Renderer>(
+        &mut self,
+        ctx: &mut R,
+        camera_x: i32,
+        blend: f32,
+        color: (u8, u8, u8),
+        theme: &Theme,
+    ) {
+        // blink between two colors while flashing
+        let color = if self.flash_timer % 2 == 1 {
+            theme.text
+        } else if self.shielded {
+            theme.shield
+        } else {
+            color
+        };
+        // wings up right after a flap, resting otherwise
+        let glyph = if self.anim_timer > 0 {
+            glyph::PLAYER_FLAP
+        } else {
+            glyph::PLAYER
+        };
+        let y = self.prev_y + (self.exact_y - self.prev_y) * blend;
+        draw::set(
+            ctx,
+            self.x - camera_x,
+            y.round() as i32,
+            color,
+            BLACK,
+            glyph,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{course::CourseObstacle, obstacle::Obstacle};
+
+    const DT: f32 = FRAME_DURATION / 1000.0;
+
+    // upside down everything the player does is the same, mirrored:
+    // falling, the speed cap, flapping and holding the flap
+    #[test]
+    fn flipped_gravity_mirrors_normal_gravity() {
+        let physics = PhysicsConfig::default();
+        let mut down = Player::new(0, 25, &physics);
+        let mut up = Player::new(0, 25, &physics);
+        up.gravity_dir = -1.0;
+        for frame in 0..12 {
+            if frame == 6 {
+                assert!(down.flap(&physics) && up.flap(&physics));
+            }
+            down.flap_held = frame < 8;
+            up.flap_held = frame < 8;
+            down.gravity_and_move(DT, &physics, 0.0);
+            up.gravity_and_move(DT, &physics, 0.0);
+            assert!(
+                (down.velocity + up.velocity).abs() < 1e-4,
+                "frame {}",
+                frame
+            );
+            assert!((down.exact_y - 25.0 - (25.0 - up.exact_y)).abs() < 1e-4);
+            assert!(down.velocity.abs() <= physics.max_velocity.max(-physics.max_flap_impulse));
+        }
+    }
+
+    // the wind pushes a flipped player the same way it does any other
+    #[test]
+    fn wind_blows_the_same_way_either_way_up() {
+        let physics = PhysicsConfig {
+            gravity: 0.0,
+            ..PhysicsConfig::default()
+        };
+        let mut down = Player::new(0, 25, &physics);
+        let mut up = Player::new(0, 25, &physics);
+        up.gravity_dir = -1.0;
+        down.gravity_and_move(DT, &physics, -0.5);
+        up.gravity_and_move(DT, &physics, -0.5);
+        assert!(down.exact_y < 25.0);
+        assert!((down.exact_y - up.exact_y).abs() < 1e-4);
+    }
+
+    // physics moves exact_y by fractions, y is always the row it rounds to
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn y_is_the_row_exact_y_rounds_to() {
+        let physics = PhysicsConfig::default();
+        let mut player = Player::new(0, 25, &physics);
+        for &(exact_y, y) in &[(4.49, 4), (4.5, 5), (4.51, 5), (0.4, 0), (49.6, 50)] {
+            player.move_to(exact_y);
+            assert_eq!(player.y, y, "{exact_y}");
+        }
+        player.move_to(10.0);
+        assert!(player.flap(&physics));
+        for _ in 0..30 {
+            player.gravity_and_move(DT / 3.0, &physics, 0.0);
+            assert_eq!(player.y, player.exact_y.round() as i32);
+        }
+    }
+
+    // collisions only look at the row, wherever in it the player is
+    #[test]
+    fn a_player_collides_as_the_row_it_is_in() {
+        let physics = PhysicsConfig::default();
+        let pipe = Obstacle::planned(10, CourseObstacle { gap_y: 25, size: 4 }, 50);
+        for row in 15..35_i16 {
+            let whole = Player::new(10, i32::from(row), &physics);
+            let hit = pipe.hit_obstacle(&whole, 9);
+            for &offset in &[-0.49, -0.2, 0.2, 0.49] {
+                let mut player = Player::new(10, i32::from(row), &physics);
+                player.move_to(f32::from(row) + offset);
+                assert_eq!(pipe.hit_obstacle(&player, 9), hit, "{row} at {offset}");
+            }
+        }
+    }
+
+    // the highest row a flap from row 30 gets to, holding the flap key
+    // for the first held_frames physics frames
+    fn peak_of_flap(held_frames: i32) -> f32 {
+        let physics = PhysicsConfig::default();
+        let mut player = Player::new(0, 30, &physics);
+        assert!(player.flap(&physics));
+        let mut peak = player.exact_y;
+        for frame in 0..30 {
+            player.flap_held = frame < held_frames;
+            player.gravity_and_move(DT, &physics, 0.0);
+            peak = peak.min(player.exact_y);
+        }
+        peak
+    }
+
+    #[test]
+    fn a_held_flap_lifts_higher_than_a_tap() {
+        let (tap, held) = (peak_of_flap(0), peak_of_flap(FLAP_HOLD_FRAMES));
+        assert!(
+            held < tap - 1.0,
+            "held to {} against a tap to {}",
+            held,
+            tap
+        );
+        // holding on past the hold frames lifts no further
+        assert!((peak_of_flap(30) - held).abs() < 1e-4);
+    }
+
+    // however long the key is held the flap never gets faster than the cap
+    #[test]
+    fn a_held_flap_is_capped() {
+        let physics = PhysicsConfig::default();
+        let mut player = Player::new(0, 30, &physics);
+        assert!(player.flap(&physics));
+        player.flap_held = true;
+        for _ in 0..10 {
+            player.gravity_and_move(DT, &physics, 0.0);
+            assert!(player.velocity >= physics.max_flap_impulse);
+        }
+    }
+}
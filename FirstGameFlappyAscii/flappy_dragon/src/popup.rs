@@ -0,0 +1,54 @@
+use crate::{renderer::Renderer, theme::Theme, update::Update};
+use bracket_lib::prelude::*;
+
+// real seconds a popup stays up
+const POPUP_SECONDS: f32 = 0.8;
+
+// rows per real second a popup floats up by
+const RISE_SPEED: f32 = 4.0;
+
+// "+1", "+3 BONUS" and the like, floating up from where points were earned
+// only ever drawn, nothing in the game reads them
+#[derive(Debug)]
+pub struct ScorePopup {
+    // screen column, it stays put while the world scrolls past
+    // so it can be read next to the player
+    x: i32,
+    // screen row, not snapped to a cell while it rises
+    y: f32,
+    text: String,
+    // seconds left before it disappears
+    life: f32,
+}
+
+impl ScorePopup {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(x: i32, y: i32, text: String) -> Self {
+        ScorePopup {
+            x,
+            y: y as f32,
+            text,
+            life: POPUP_SECONDS,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.life > 0.0
+    }
+
+    // fades from the coin color to the ghost color as it runs out
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn render<R: Renderer>(&self, ctx: &mut R, theme: &Theme) {
+        let age = 1.0 - self.life / POPUP_SECONDS;
+        let color = RGB::named(theme.coin).lerp(RGB::named(theme.ghost), age);
+        ctx.print_color(self.x, self.y.round() as i32, color, BLACK, &self.text);
+    }
+}
+
+impl Update for ScorePopup {
+    // advance by dt real seconds
+    fn update(&mut self, dt: f32) {
+        self.y -= RISE_SPEED * dt;
+        self.life -= dt;
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    draw, glyph,
+    player::Player,
+    renderer::{Renderable, Renderer},
+    theme::Theme,
+    update::Update,
+};
+use bracket_lib::prelude::*;
+
+// what a power-up does once collected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerUpKind {
+    // survive one crash
+    Shield,
+    // slow the whole world down for a while
+    SlowMo,
+    // gravity pulls the player up for a while, flaps push down
+    GravityFlip,
+    // coins nearby are pulled in to the player for a while
+    Magnet,
+}
+
+impl PowerUpKind {
+    // pick a kind of power-up to spawn
+    pub fn random(random: &mut RandomNumberGenerator) -> Self {
+        match random.range(0, 4) {
+            0 => PowerUpKind::Shield,
+            1 => PowerUpKind::SlowMo,
+            2 => PowerUpKind::GravityFlip,
+            _ => PowerUpKind::Magnet,
+        }
+    }
+}
+
+// a collectible floating in the world
+#[derive(Debug)]
+pub struct PowerUp {
+    // world-space column, like obstacles
+    pub x: i32,
+    // screen row
+    pub y: i32,
+    pub kind: PowerUpKind,
+}
+
+impl PowerUp {
+    pub fn new(x: i32, y: i32, kind: PowerUpKind) -> Self {
+        PowerUp { x, y, kind }
+    }
+
+    // the player picks a power-up up by flying next to it
+    pub fn touches(&self, player: &Player) -> bool {
+        (self.x - player.x).abs() <= 1 && (self.y - player.y).abs() <= 1
+    }
+}
+
+// only scrolls past
+impl Update for PowerUp {}
+
+impl Renderable for PowerUp {
+    fn render<R: Renderer>(&self, ctx: &mut R, player_x: i32, theme: &Theme) {
+        let (color, glyph) = match self.kind {
+            PowerUpKind::Shield => (theme.shield, glyph::SHIELD),
+            PowerUpKind::SlowMo => (theme.slowmo, glyph::SLOWMO),
+            PowerUpKind::GravityFlip => (theme.flip, glyph::GRAVITY_FLIP),
+            PowerUpKind::Magnet => (theme.magnet, glyph::MAGNET),
+        };
+        draw::set(ctx, self.x - player_x, self.y, color, BLACK, glyph);
+    }
+}
@@ -0,0 +1,314 @@
+// everything the game draws with, so drawing code doesn't need a window
+// BTerm is the real thing, anything else can record the calls instead
+use crate::{draw, theme::Theme};
+use bracket_lib::prelude::*;
+
+pub trait Renderer {
+    // width and height of the console, in cells
+    fn size(&self) -> (i32, i32);
+
+    fn cls(&mut self);
+
+    fn cls_bg<BG: Into<RGBA>>(&mut self, bg: BG);
+
+    fn set<FG: Into<RGBA>, BG: Into<RGBA>>(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        glyph: FontCharType,
+    );
+
+    fn set_bg<BG: Into<RGBA>>(&mut self, x: i32, y: i32, bg: BG);
+
+    fn print_centered<S: ToString>(&mut self, y: i32, text: S);
+
+    fn print_color<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    );
+
+    fn print_color_centered<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    );
+}
+
+// anything in the world that draws itself,
+// player_x is the world column at the left edge of the screen
+pub trait Renderable {
+    fn render<R: Renderer>(&self, ctx: &mut R, player_x: i32, theme: &Theme);
+}
+
+// draw a whole kind of entity, later ones on top of earlier ones
+pub fn render_all<R: Renderer, T: Renderable>(
+    ctx: &mut R,
+    entities: &[T],
+    player_x: i32,
+    theme: &Theme,
+) {
+    for entity in entities {
+        entity.render(ctx, player_x, theme);
+    }
+}
+
+// draws everything dx, dy cells away from where it is asked for,
+// for the screen shake, whatever ends up off the console is skipped
+pub struct Offset<'a, R: Renderer> {
+    inner: &'a mut R,
+    dx: i32,
+    dy: i32,
+}
+
+impl<'a, R: Renderer> Offset<'a, R> {
+    pub fn new(inner: &'a mut R, dx: i32, dy: i32) -> Self {
+        Offset { inner, dx, dy }
+    }
+}
+
+impl<R: Renderer> Renderer for Offset<'_, R> {
+    fn size(&self) -> (i32, i32) {
+        self.inner.size()
+    }
+
+    fn cls(&mut self) {
+        self.inner.cls();
+    }
+
+    fn cls_bg<BG: Into<RGBA>>(&mut self, bg: BG) {
+        self.inner.cls_bg(bg);
+    }
+
+    fn set<FG: Into<RGBA>, BG: Into<RGBA>>(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        glyph: FontCharType,
+    ) {
+        draw::set(self.inner, x + self.dx, y + self.dy, fg, bg, glyph);
+    }
+
+    fn set_bg<BG: Into<RGBA>>(&mut self, x: i32, y: i32, bg: BG) {
+        draw::set_bg(self.inner, x + self.dx, y + self.dy, bg);
+    }
+
+    // white on black, the colors print_centered leaves after a cls
+    fn print_centered<S: ToString>(&mut self, y: i32, text: S) {
+        self.print_color_centered(y, WHITE, BLACK, text);
+    }
+
+    // bracket-lib skips the cells of a print that are off the console
+    fn print_color<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    ) {
+        self.inner
+            .print_color(x + self.dx, y + self.dy, fg, bg, text);
+    }
+
+    // centered the way bracket-lib centers
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn print_color_centered<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    ) {
+        let text = text.to_string();
+        let x = self.size().0 / 2 - text.chars().count() as i32 / 2;
+        self.print_color(x, y, fg, bg, text);
+    }
+}
+
+// straight through to bracket-lib
+impl Renderer for BTerm {
+    #[allow(clippy::cast_possible_wrap)]
+    fn size(&self) -> (i32, i32) {
+        let (width, height) = self.get_char_size();
+        (width as i32, height as i32)
+    }
+
+    fn cls(&mut self) {
+        BTerm::cls(self);
+    }
+
+    fn cls_bg<BG: Into<RGBA>>(&mut self, bg: BG) {
+        BTerm::cls_bg(self, bg);
+    }
+
+    fn set<FG: Into<RGBA>, BG: Into<RGBA>>(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        glyph: FontCharType,
+    ) {
+        BTerm::set(self, x, y, fg, bg, glyph);
+    }
+
+    fn set_bg<BG: Into<RGBA>>(&mut self, x: i32, y: i32, bg: BG) {
+        BTerm::set_bg(self, x, y, bg);
+    }
+
+    fn print_centered<S: ToString>(&mut self, y: i32, text: S) {
+        BTerm::print_centered(self, y, text);
+    }
+
+    fn print_color<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    ) {
+        BTerm::print_color(self, x, y, fg, bg, text);
+    }
+
+    fn print_color_centered<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    ) {
+        BTerm::print_color_centered(self, y, fg, bg, text);
+    }
+}
+
+// a console that keeps a list of what was drawn on it instead of a window,
+// drawing a cell or a background off it panics
+#[cfg(test)]
+pub struct Recorder {
+    pub width: i32,
+    pub height: i32,
+    // x, y and glyph of every set, in order
+    pub cells: Vec<(i32, i32, FontCharType)>,
+    // x and y of every set_bg, in order
+    pub backgrounds: Vec<(i32, i32)>,
+    // x, y and text of every print, centered ones where they started
+    pub prints: Vec<(i32, i32, String)>,
+}
+
+#[cfg(test)]
+use std::convert::TryFrom;
+
+#[cfg(test)]
+impl Recorder {
+    pub fn new(width: i32, height: i32) -> Self {
+        Recorder {
+            width,
+            height,
+            cells: Vec::new(),
+            backgrounds: Vec::new(),
+            prints: Vec::new(),
+        }
+    }
+
+    // what row y reads like, cells first and prints over them,
+    // trailing blanks left off
+    #[allow(clippy::cast_sign_loss)]
+    pub fn row(&self, y: i32) -> String {
+        let mut row = vec![' '; self.width as usize];
+        for &(x, _, glyph) in self.cells.iter().filter(|cell| cell.1 == y) {
+            row[x as usize] = to_char(u8::try_from(glyph).unwrap_or(b'?'));
+        }
+        for (x, _, text) in self.prints.iter().filter(|print| print.1 == y) {
+            for (x, c) in (*x..).zip(text.chars()) {
+                if let Some(cell) = usize::try_from(x).ok().and_then(|x| row.get_mut(x)) {
+                    *cell = c;
+                }
+            }
+        }
+        row.into_iter().collect::<String>().trim_end().to_string()
+    }
+
+    fn check(&self, x: i32, y: i32) {
+        assert!(
+            draw::in_bounds(x, y, self.width, self.height),
+            "drawn at {}, {} off a {} x {} console",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+    }
+}
+
+#[cfg(test)]
+impl Renderer for Recorder {
+    fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    fn cls(&mut self) {
+        self.cells.clear();
+        self.backgrounds.clear();
+        self.prints.clear();
+    }
+
+    fn cls_bg<BG: Into<RGBA>>(&mut self, _bg: BG) {
+        self.cls();
+    }
+
+    fn set<FG: Into<RGBA>, BG: Into<RGBA>>(
+        &mut self,
+        x: i32,
+        y: i32,
+        _fg: FG,
+        _bg: BG,
+        glyph: FontCharType,
+    ) {
+        self.check(x, y);
+        self.cells.push((x, y, glyph));
+    }
+
+    fn set_bg<BG: Into<RGBA>>(&mut self, x: i32, y: i32, _bg: BG) {
+        self.check(x, y);
+        self.backgrounds.push((x, y));
+    }
+
+    fn print_centered<S: ToString>(&mut self, y: i32, text: S) {
+        self.print_color_centered(y, WHITE, BLACK, text);
+    }
+
+    fn print_color<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        x: i32,
+        y: i32,
+        _fg: FG,
+        _bg: BG,
+        text: S,
+    ) {
+        self.prints.push((x, y, text.to_string()));
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn print_color_centered<FG: Into<RGBA>, BG: Into<RGBA>, S: ToString>(
+        &mut self,
+        y: i32,
+        fg: FG,
+        bg: BG,
+        text: S,
+    ) {
+        let text = text.to_string();
+        let x = self.width / 2 - text.chars().count() as i32 / 2;
+        self.print_color(x, y, fg, bg, text);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    config::{ScoringMode, Screen},
+    course::CourseObstacle,
+    Difficulty, FrameInput, RunKind,
+};
+use serde::{Deserialize, Serialize};
+
+// every finished run is saved here, ready for --replay
+pub const LAST_REPLAY_FILE: &str = "last.replay";
+
+// everything needed to fly a run again exactly as it went,
+// saved as JSON so it can be attached to a bug report
+// the course comes from the seed, so only the inputs are recorded
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Replay {
+    // what the random numbers of the run started from
+    pub seed: u64,
+    // the difficulty and screen size change the course, so they are kept too
+    pub difficulty: Difficulty,
+    pub screen: Screen,
+    pub run: RunKind,
+    // total score the run ended on, to check the replay against
+    pub score: i32,
+    // how that score was counted
+    #[serde(default)]
+    pub scoring: ScoringMode,
+    // the handcrafted course the run was flown on, empty for a random one
+    #[serde(default)]
+    pub course: Vec<CourseObstacle>,
+    // the input of every frame of the run, in order
+    frames: Vec<FrameInput>,
+    // next frame to play back
+    #[serde(skip)]
+    position: usize,
+}
+
+impl Replay {
+    // an empty recording of a run about to start
+    pub fn new(seed: u64, difficulty: Difficulty, screen: Screen, run: RunKind) -> Self {
+        Replay {
+            seed,
+            difficulty,
+            screen,
+            run,
+            score: 0,
+            scoring: ScoringMode::Obstacles,
+            course: Vec::new(),
+            frames: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        serde_json::from_str(&text).map_err(|err| err.to_string())
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|err| err.to_string())
+            .and_then(|text| std::fs::write(LAST_REPLAY_FILE, text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            eprintln!("Could not save replay to {LAST_REPLAY_FILE}: {err}");
+        }
+    }
+
+    pub fn record(&mut self, input: FrameInput) {
+        self.frames.push(input);
+    }
+
+    // the input of the next frame, None once the recording runs out
+    pub fn next_frame(&mut self) -> Option<FrameInput> {
+        let input = self.frames.get(self.position).copied()?;
+        self.position += 1;
+        Some(input)
+    }
+}
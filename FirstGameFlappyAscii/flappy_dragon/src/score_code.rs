@@ -0,0 +1,345 @@
+use crate::{
+    config::{ScoringMode, Screen},
+    leaderboard::letter_for_key,
+    Difficulty,
+};
+use bracket_lib::prelude::VirtualKeyCode;
+use std::{convert::TryFrom, fmt};
+
+// the digits of a code, base 36 so it stays short and can be typed
+// the screen size is written in base 10, the way it is talked about
+const DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// most digits a u64 takes, 20 in base 10 and 13 in base 36
+const MAX_DIGITS: usize = 20;
+
+// longest code there is: two letters, a u64 and an i32 in base 36,
+// the two i32s of the screen in base 10, a check and what goes between
+pub const MAX_CODE_LENGTH: usize = 2 + 13 + 1 + 7 + 1 + 10 + 1 + 10 + 1 + 2;
+
+// a finished run someone can share, enough for anyone else to fly
+// the same course and see whether they can beat it
+// written as DIFFICULTY SCORING SEED-SCORE-WIDTHxHEIGHT-CHECK,
+// e.g. "NO1Z4K-C-80x50-PX", the course only comes out the same on that screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreCode {
+    pub seed: u64,
+    pub score: i32,
+    pub difficulty: Difficulty,
+    pub scoring: ScoringMode,
+    pub screen: Screen,
+}
+
+impl ScoreCode {
+    // anything that isn't a code this game wrote is turned down,
+    // the check catches a mistyped character
+    pub fn decode(code: &str) -> Result<Self, String> {
+        let code = code.trim().to_ascii_uppercase();
+        let (body, check) = code
+            .rsplit_once('-')
+            .ok_or("a code looks like NO1Z4K-C-80x50-PX")?;
+        if checksum(body) != check {
+            return Err("that code has a typo in it".to_string());
+        }
+        let (head, rest) = body
+            .split_once('-')
+            .ok_or("a code looks like NO1Z4K-C-80x50-PX")?;
+        let (score, screen) = rest
+            .split_once('-')
+            .ok_or("a code looks like NO1Z4K-C-80x50-PX")?;
+        let mut chars = head.chars();
+        let difficulty = match chars.next() {
+            Some('E') => Difficulty::Easy,
+            Some('N') => Difficulty::Normal,
+            Some('H') => Difficulty::Hard,
+            _ => return Err("unknown difficulty in the code".to_string()),
+        };
+        let scoring = match chars.next() {
+            Some('O') => ScoringMode::Obstacles,
+            Some('D') => ScoringMode::Distance,
+            Some('C') => ScoringMode::Coins,
+            _ => return Err("unknown scoring in the code".to_string()),
+        };
+        let seed = from_digits(chars.as_str(), 36).ok_or("the seed in the code is not a number")?;
+        let score = from_digits(score, 36)
+            .and_then(|score| i32::try_from(score).ok())
+            .ok_or("the score in the code is not a number")?;
+        let side = |side| from_digits(side, 10).and_then(|side| i32::try_from(side).ok());
+        let screen = screen
+            .split_once('X')
+            .and_then(|(width, height)| {
+                Some(Screen {
+                    width: side(width)?,
+                    height: side(height)?,
+                })
+            })
+            .ok_or("the screen in the code is not WIDTHxHEIGHT")?;
+        Ok(ScoreCode {
+            seed,
+            score,
+            difficulty,
+            scoring,
+            screen,
+        })
+    }
+}
+
+// written straight into the formatter, so a code shown every frame
+// can go into a reused buffer without allocating
+impl fmt::Display for ScoreCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let difficulty = match self.difficulty {
+            Difficulty::Easy => b'E',
+            Difficulty::Normal => b'N',
+            Difficulty::Hard => b'H',
+        };
+        let scoring = match self.scoring {
+            ScoringMode::Obstacles => b'O',
+            ScoringMode::Distance => b'D',
+            ScoringMode::Coins => b'C',
+        };
+        let number = |value: i32, radix| digits(u64::try_from(value).unwrap_or_default(), radix);
+        let (seed, seed_length) = digits(self.seed, 36);
+        let (score, score_length) = number(self.score, 36);
+        let (width, width_length) = number(self.screen.width, 10);
+        let (height, height_length) = number(self.screen.height, 10);
+        let head = [difficulty, scoring];
+        let body = head
+            .iter()
+            .chain(&seed[..seed_length])
+            .chain(b"-")
+            .chain(&score[..score_length])
+            .chain(b"-")
+            .chain(&width[..width_length])
+            .chain(b"x")
+            .chain(&height[..height_length]);
+        // the check is of the code as decode reads it, upper case
+        let mut sum = 0;
+        for &byte in body {
+            sum = add_to_checksum(sum, byte.to_ascii_uppercase());
+            write!(f, "{}", char::from(byte))?;
+        }
+        let [high, low] = check_digits(sum);
+        write!(f, "-{}{}", char::from(high), char::from(low))
+    }
+}
+
+// the character a key types into a code, None for anything else
+pub fn char_for_key(key: VirtualKeyCode) -> Option<char> {
+    let digit = match key {
+        VirtualKeyCode::Key0 | VirtualKeyCode::Numpad0 => '0',
+        VirtualKeyCode::Key1 | VirtualKeyCode::Numpad1 => '1',
+        VirtualKeyCode::Key2 | VirtualKeyCode::Numpad2 => '2',
+        VirtualKeyCode::Key3 | VirtualKeyCode::Numpad3 => '3',
+        VirtualKeyCode::Key4 | VirtualKeyCode::Numpad4 => '4',
+        VirtualKeyCode::Key5 | VirtualKeyCode::Numpad5 => '5',
+        VirtualKeyCode::Key6 | VirtualKeyCode::Numpad6 => '6',
+        VirtualKeyCode::Key7 | VirtualKeyCode::Numpad7 => '7',
+        VirtualKeyCode::Key8 | VirtualKeyCode::Numpad8 => '8',
+        VirtualKeyCode::Key9 | VirtualKeyCode::Numpad9 => '9',
+        VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => '-',
+        key => return letter_for_key(key),
+    };
+    Some(digit)
+}
+
+// the digits of value in a radix up to 36, most significant first,
+// and how many there are
+fn digits(mut value: u64, radix: u64) -> ([u8; MAX_DIGITS], usize) {
+    let mut digits = [0; MAX_DIGITS];
+    let mut length = 0;
+    loop {
+        digits[length] = DIGITS[usize::try_from(value % radix).unwrap_or_default()];
+        length += 1;
+        value /= radix;
+        if value == 0 {
+            break;
+        }
+    }
+    digits[..length].reverse();
+    (digits, length)
+}
+
+// None for an empty string, a character that isn't a digit of the radix
+// or a number too big for a u64
+fn from_digits(text: &str, radix: u32) -> Option<u64> {
+    if text.is_empty() {
+        return None;
+    }
+    text.chars().try_fold(0_u64, |value, c| {
+        let digit = u64::from(c.to_digit(radix)?);
+        value.checked_mul(u64::from(radix))?.checked_add(digit)
+    })
+}
+
+// two base 36 digits worked out from everything before them
+fn checksum(body: &str) -> String {
+    let sum = body.bytes().fold(0, add_to_checksum);
+    check_digits(sum)
+        .iter()
+        .map(|&digit| char::from(digit))
+        .collect()
+}
+
+// the two digits of a checksum, leading zero included
+fn check_digits(sum: u64) -> [u8; 2] {
+    let digit = |value: u64| DIGITS[usize::try_from(value % 36).unwrap_or_default()];
+    [digit(sum / 36), digit(sum)]
+}
+
+// the checksum of a body so far, with one more byte of it
+fn add_to_checksum(sum: u64, byte: u8) -> u64 {
+    (sum * 31 + u64::from(byte)) % 1296
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_code_decodes_to_the_run_it_was_written_for() {
+        let difficulties = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+        let scorings = [
+            ScoringMode::Obstacles,
+            ScoringMode::Distance,
+            ScoringMode::Coins,
+        ];
+        let screens = [
+            Screen::default(),
+            Screen {
+                width: 30,
+                height: 20,
+            },
+            Screen {
+                width: 1920,
+                height: 5,
+            },
+            Screen {
+                width: i32::MAX,
+                height: i32::MAX,
+            },
+        ];
+        for &difficulty in &difficulties {
+            for &scoring in &scorings {
+                for &screen in &screens {
+                    for &(seed, score) in &[(0, 0), (1_234_567, 42), (u64::MAX, i32::MAX)] {
+                        let run = ScoreCode {
+                            seed,
+                            score,
+                            difficulty,
+                            scoring,
+                            screen,
+                        };
+                        let code = run.to_string();
+                        assert!(code.len() <= MAX_CODE_LENGTH, "{} is too long", code);
+                        assert_eq!(ScoreCode::decode(&code), Ok(run), "{code}");
+                    }
+                }
+            }
+        }
+    }
+
+    // the screen is covered by the check like the rest,
+    // a code can't be moved onto another screen by editing it
+    #[test]
+    fn a_code_is_for_the_screen_it_was_written_on() {
+        let run = ScoreCode {
+            seed: 99,
+            score: 5,
+            difficulty: Difficulty::Normal,
+            scoring: ScoringMode::Obstacles,
+            screen: Screen {
+                width: 100,
+                height: 40,
+            },
+        };
+        let code = run.to_string();
+        assert!(
+            code.contains("-100x40-"),
+            "{} doesn't show the screen",
+            code
+        );
+        assert_ne!(
+            ScoreCode::decode(&code).map(|code| code.screen),
+            Ok(Screen::default())
+        );
+        let moved = code.replace("100x40", "80x50");
+        assert_eq!(
+            ScoreCode::decode(&moved),
+            Err("that code has a typo in it".to_string())
+        );
+    }
+
+    #[test]
+    fn codes_are_read_whatever_the_case_and_spacing() {
+        let run = ScoreCode {
+            seed: 99,
+            score: 5,
+            difficulty: Difficulty::Easy,
+            scoring: ScoringMode::Distance,
+            screen: Screen::default(),
+        };
+        let code = format!("  {}\n", run.to_string().to_ascii_lowercase());
+        assert_eq!(ScoreCode::decode(&code), Ok(run));
+    }
+
+    fn to_base36(value: u64) -> String {
+        let (digits, length) = digits(value, 36);
+        digits[..length]
+            .iter()
+            .map(|&digit| char::from(digit))
+            .collect()
+    }
+
+    // a body with the check it needs, so only the body can be wrong
+    fn checked(body: &str) -> String {
+        format!("{body}-{}", checksum(body))
+    }
+
+    #[test]
+    fn malformed_codes_are_turned_down() {
+        let typo = {
+            let mut code = ScoreCode {
+                seed: 99,
+                score: 5,
+                difficulty: Difficulty::Easy,
+                scoring: ScoringMode::Distance,
+                screen: Screen::default(),
+            }
+            .to_string();
+            code.replace_range(2..3, if code[2..3] == *"Z" { "Y" } else { "Z" });
+            code
+        };
+        let too_big = format!("NO{}", to_base36(u64::MAX));
+        let malformed = [
+            String::new(),
+            "-".to_string(),
+            "NO1Z4K".to_string(),
+            typo,
+            checked("NO1Z4K"),
+            checked("NO1Z4K-C"),
+            checked("XO1Z4K-C-80X50"),
+            checked("NX1Z4K-C-80X50"),
+            checked("N-C-80X50"),
+            checked("NO-C-80X50"),
+            checked("NO1Z4K--80X50"),
+            checked("NO1Z_4K-C-80X50"),
+            checked(&format!("{too_big}0-C-80X50")),
+            checked(&format!("NO1-{}-80X50", to_base36(u64::from(u32::MAX)))),
+            checked("NO1Z4K-C-"),
+            checked("NO1Z4K-C-80"),
+            checked("NO1Z4K-C-80X"),
+            checked("NO1Z4K-C-X50"),
+            checked("NO1Z4K-C-8AX50"),
+            checked("NO1Z4K-C-80X50X1"),
+            checked("NO1Z4K-C-2147483648X50"),
+        ];
+        for code in &malformed {
+            assert!(
+                ScoreCode::decode(code).is_err(),
+                "{:?} was read as a code",
+                code
+            );
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    args::{MIN_HEIGHT, MIN_WIDTH},
+    config::{ScoringMode, Screen},
+    theme::ThemePreset,
+    Difficulty, KeyBindings,
+};
+use bracket_lib::prelude::VirtualKeyCode;
+use serde::{Deserialize, Serialize};
+
+// TOML file with the settings, every key is optional
+const SETTINGS_FILE: &str = "flappy.toml";
+
+// highest master volume, in percent
+pub const MAX_VOLUME: u8 = 100;
+
+// how much one press of left/right changes the volume
+pub const VOLUME_STEP: u8 = 10;
+
+// keys that can be bound in the settings file, by their Debug names
+const BINDABLE_KEYS: [VirtualKeyCode; 56] = [
+    VirtualKeyCode::A,
+    VirtualKeyCode::B,
+    VirtualKeyCode::C,
+    VirtualKeyCode::D,
+    VirtualKeyCode::E,
+    VirtualKeyCode::F,
+    VirtualKeyCode::G,
+    VirtualKeyCode::H,
+    VirtualKeyCode::I,
+    VirtualKeyCode::J,
+    VirtualKeyCode::K,
+    VirtualKeyCode::L,
+    VirtualKeyCode::M,
+    VirtualKeyCode::N,
+    VirtualKeyCode::O,
+    VirtualKeyCode::P,
+    VirtualKeyCode::Q,
+    VirtualKeyCode::R,
+    VirtualKeyCode::S,
+    VirtualKeyCode::T,
+    VirtualKeyCode::U,
+    VirtualKeyCode::V,
+    VirtualKeyCode::W,
+    VirtualKeyCode::X,
+    VirtualKeyCode::Y,
+    VirtualKeyCode::Z,
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+    VirtualKeyCode::F1,
+    VirtualKeyCode::F2,
+    VirtualKeyCode::F3,
+    VirtualKeyCode::F4,
+    VirtualKeyCode::F5,
+    VirtualKeyCode::F6,
+    VirtualKeyCode::F7,
+    VirtualKeyCode::F8,
+    VirtualKeyCode::F9,
+    VirtualKeyCode::F10,
+    VirtualKeyCode::F11,
+    VirtualKeyCode::F12,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::Return,
+    VirtualKeyCode::Escape,
+    VirtualKeyCode::Tab,
+    VirtualKeyCode::Up,
+    VirtualKeyCode::Down,
+    VirtualKeyCode::Left,
+    VirtualKeyCode::Right,
+];
+
+// everything the player can change, on the settings screen or in the file
+// the game reads them from here, apart from the difficulty's tunables and
+// the scoring: runs are played with the copies in State::config, so the
+// settings screen changes both, and a replay only changes the config
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    // master volume, 0..=MAX_VOLUME
+    pub volume: u8,
+    // difficulty used for every new run
+    pub difficulty: Difficulty,
+    // colors everything is drawn in
+    pub theme: ThemePreset,
+    // how the score of a run is counted
+    pub scoring: ScoringMode,
+    // window size, --width and --height override it for one session
+    pub screen: Screen,
+    // controls, only changed in the file
+    pub keys: KeyBindings,
+    // high-contrast colors and a score that stands out, for low vision
+    pub accessibility: bool,
+}
+
+impl Default for Settings {
+    // full volume, normal difficulty, the original colors and controls
+    fn default() -> Self {
+        Settings {
+            volume: MAX_VOLUME,
+            difficulty: Difficulty::Normal,
+            theme: ThemePreset::Classic,
+            scoring: ScoringMode::Obstacles,
+            screen: Screen::default(),
+            keys: KeyBindings::default(),
+            accessibility: false,
+        }
+    }
+}
+
+// what flappy.toml looks like, anything left out keeps its default
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsFile {
+    width: Option<i32>,
+    height: Option<i32>,
+    volume: Option<u8>,
+    difficulty: Option<Difficulty>,
+    theme: Option<ThemePreset>,
+    scoring: Option<ScoringMode>,
+    accessibility: Option<bool>,
+    keys: KeysFile,
+}
+
+// the [keys] table, key names as the settings screen shows them
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct KeysFile {
+    flap: Option<String>,
+    flap2: Option<String>,
+    pause: Option<String>,
+    play: Option<String>,
+    quit: Option<String>,
+    mute: Option<String>,
+    fps: Option<String>,
+    screenshot: Option<String>,
+}
+
+impl Settings {
+    // read the saved settings
+    // a missing file is all defaults, a file that doesn't parse is reported
+    // and ignored, and a bad value only loses that one setting
+    pub fn load() -> Self {
+        let mut settings = Settings::default();
+        let Ok(text) = std::fs::read_to_string(SETTINGS_FILE) else {
+            return settings;
+        };
+        let file: SettingsFile = match toml::from_str(&text) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("warning: ignoring {SETTINGS_FILE}, it could not be read: {err}");
+                return settings;
+            }
+        };
+        if let Some(volume) = file.volume {
+            settings.volume = volume.min(MAX_VOLUME);
+        }
+        if let Some(difficulty) = file.difficulty {
+            settings.difficulty = difficulty;
+        }
+        if let Some(theme) = file.theme {
+            settings.theme = theme;
+        }
+        if let Some(scoring) = file.scoring {
+            settings.scoring = scoring;
+        }
+        if let Some(accessibility) = file.accessibility {
+            settings.accessibility = accessibility;
+        }
+        match file.width {
+            Some(width) if width < MIN_WIDTH => {
+                eprintln!("warning: width in {SETTINGS_FILE} must be at least {MIN_WIDTH}");
+            }
+            Some(width) => settings.screen.width = width,
+            None => {}
+        }
+        match file.height {
+            Some(height) if height < MIN_HEIGHT => {
+                eprintln!("warning: height in {SETTINGS_FILE} must be at least {MIN_HEIGHT}");
+            }
+            Some(height) => settings.screen.height = height,
+            None => {}
+        }
+        let keys = &mut settings.keys;
+        for (binding, name) in [
+            (&mut keys.flap, file.keys.flap),
+            (&mut keys.flap2, file.keys.flap2),
+            (&mut keys.pause, file.keys.pause),
+            (&mut keys.play, file.keys.play),
+            (&mut keys.quit, file.keys.quit),
+            (&mut keys.mute, file.keys.mute),
+            (&mut keys.fps, file.keys.fps),
+            (&mut keys.screenshot, file.keys.screenshot),
+        ] {
+            if let Some(name) = name {
+                match key_from_name(&name) {
+                    Some(key) => *binding = key,
+                    None => eprintln!("warning: unknown key {name} in {SETTINGS_FILE}"),
+                }
+            }
+        }
+        settings
+    }
+
+    // write every setting out, so the file shows what can be changed
+    pub fn save(self) {
+        let keys = self.keys;
+        let file = SettingsFile {
+            width: Some(self.screen.width),
+            height: Some(self.screen.height),
+            volume: Some(self.volume),
+            difficulty: Some(self.difficulty),
+            theme: Some(self.theme),
+            scoring: Some(self.scoring),
+            accessibility: Some(self.accessibility),
+            keys: KeysFile {
+                flap: Some(format!("{:?}", keys.flap)),
+                flap2: Some(format!("{:?}", keys.flap2)),
+                pause: Some(format!("{:?}", keys.pause)),
+                play: Some(format!("{:?}", keys.play)),
+                quit: Some(format!("{:?}", keys.quit)),
+                mute: Some(format!("{:?}", keys.mute)),
+                fps: Some(format!("{:?}", keys.fps)),
+                screenshot: Some(format!("{:?}", keys.screenshot)),
+            },
+        };
+        let result = toml::to_string(&file)
+            .map_err(|err| err.to_string())
+            .and_then(|text| std::fs::write(SETTINGS_FILE, text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            eprintln!("Could not save settings to {SETTINGS_FILE}: {err}");
+        }
+    }
+
+    pub fn volume_up(&mut self) {
+        self.volume = u8::min(MAX_VOLUME, self.volume.saturating_add(VOLUME_STEP));
+    }
+
+    pub fn volume_down(&mut self) {
+        self.volume = self.volume.saturating_sub(VOLUME_STEP);
+    }
+
+    // master volume as a 0.0..=1.0 factor for the audio
+    pub fn volume_factor(self) -> f32 {
+        f32::from(self.volume) / f32::from(MAX_VOLUME)
+    }
+}
+
+// the key with this Debug name, "Space", "Up", "P" and so on
+fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|key| format!("{key:?}") == name)
+}
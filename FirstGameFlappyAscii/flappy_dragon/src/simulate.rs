@@ -0,0 +1,89 @@
+// runs played without a window, for balancing the difficulty and for tests
+// everything goes through State::update, the same as a run on screen,
+// only the input comes from a policy or a list instead of the keyboard
+
+use crate::{settings::Settings, FrameInput, GameConfig, GameMode, Obstacle, Player, State};
+
+// real milliseconds per simulated frame, as if the window ran at 60 fps
+const FRAME_MS: f32 = 1000.0 / 60.0;
+
+// gives up on a run after this many frames, ten minutes of play,
+// in case the policy never crashes
+const MAX_FRAMES: u32 = 60 * 60 * 10;
+
+// play one normal run from `seed` and return its score, obstacles and coins
+// frames are rendered frames, not physics frames: each one is FRAME_MS
+// (1/60 s) of real time, which steps the physics zero or more times by
+// config.frame_duration, whatever is left over carries into the next frame
+// inputs[n] is whether the flap key went down on frame n, a tap, never held
+// the run starts straight away, without the countdown, and ends at the crash
+// that uses up the last life or after the last input, whichever comes first
+// it is played on a state in memory: no settings, records or stats are read
+// from the working directory and nothing is saved there, so the score only
+// depends on the arguments
+#[must_use]
+pub fn simulate(seed: u64, inputs: &[bool], config: &GameConfig) -> i32 {
+    let mut inputs = inputs.iter().copied();
+    run(seed, *config, |_| inputs.next())
+}
+
+// the same as simulate, but the policy is asked every frame whether to flap,
+// for at most MAX_FRAMES frames
+fn simulate_policy<P: FnMut(&State) -> bool>(seed: u64, mut policy: P) -> i32 {
+    let mut frames = 0;
+    run(seed, GameConfig::default(), |state| {
+        frames += 1;
+        (frames <= MAX_FRAMES).then(|| policy(state))
+    })
+}
+
+// play a run until it crashes or `flap` returns None
+fn run<F: FnMut(&State) -> Option<bool>>(seed: u64, config: GameConfig, mut flap: F) -> i32 {
+    let mut state = State::in_memory(config, Settings::default(), seed);
+    state.audio.set_muted(true);
+    // start would put the tunables of the difficulty back over config,
+    // a normal run is already what a new state is set up for
+    state.restart();
+    // no countdown without anyone to watch it
+    state.transition_to(GameMode::Playing);
+    while matches!(state.mode, GameMode::Playing) {
+        let Some(flap) = flap(&state) else {
+            break;
+        };
+        state.update(FrameInput {
+            frame_time_ms: FRAME_MS,
+            flap,
+            ..FrameInput::default()
+        });
+    }
+    state.total_score()
+}
+
+// a simple autopilot: flap whenever the player is falling below
+// the middle of the next gap, and leave it be with nothing to aim for
+// with gravity flipped that is falling up past it
+pub fn should_flap(player: &Player, next_obstacle: Option<&Obstacle>) -> bool {
+    next_obstacle.is_some_and(|obstacle| {
+        if player.gravity_dir < 0.0 {
+            player.y < obstacle.gap_y && player.velocity <= 0.0
+        } else {
+            player.y > obstacle.gap_y && player.velocity >= 0.0
+        }
+    })
+}
+
+// should_flap as a policy for simulate
+pub fn follow_gap(state: &State) -> bool {
+    should_flap(&state.player, state.obstacle_ahead())
+}
+
+// simulate `runs` runs with follow_gap and print how they went
+pub fn report(runs: u32) {
+    let scores: Vec<i32> = (0..u64::from(runs))
+        .map(|seed| simulate_policy(seed, follow_gap))
+        .collect();
+    let best = scores.iter().copied().max().unwrap_or(0);
+    let total: i64 = scores.iter().map(|&score| i64::from(score)).sum();
+    let average = total / i64::from(runs.max(1));
+    println!("{runs} runs, average score {average}, best {best}");
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+// JSON file with the totals of every game ever played
+const STATS_FILE: &str = "stats.json";
+
+// totals across every session
+// fields missing from the file start at 0, so new ones can be added later
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    // finished runs, races included
+    pub games_played: u32,
+    pub flaps: u64,
+    // obstacles flown past, by either racer in a race
+    pub obstacles_passed: u64,
+    // best score of a normal or daily run
+    pub best_score: i32,
+    // real seconds spent playing, pauses and menus not included
+    pub play_seconds: f64,
+}
+
+impl Stats {
+    // read the saved totals
+    // a missing file starts everything at 0, a corrupt one too, with a warning
+    pub fn load() -> Self {
+        let Ok(text) = std::fs::read_to_string(STATS_FILE) else {
+            return Stats::default();
+        };
+        serde_json::from_str(&text).unwrap_or_else(|err| {
+            eprintln!("warning: ignoring corrupt {STATS_FILE}: {err}");
+            Stats::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|err| err.to_string())
+            .and_then(|text| std::fs::write(STATS_FILE, text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            eprintln!("Could not save stats to {STATS_FILE}: {err}");
+        }
+    }
+
+    // the totals for --stats
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn print(&self) {
+        let minutes = (self.play_seconds / 60.0) as u64;
+        println!("Games played:     {}", self.games_played);
+        println!("Flaps:            {}", self.flaps);
+        println!("Obstacles passed: {}", self.obstacles_passed);
+        println!("Best score:       {}", self.best_score);
+        println!("Play time:        {}h {:02}m", minutes / 60, minutes % 60);
+    }
+}
@@ -0,0 +1,172 @@
+use bracket_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+type Color = (u8, u8, u8);
+
+// every color used to draw a run
+pub struct Theme {
+    pub player: Color,
+    // the second bird of a two-player race
+    pub player2: Color,
+    pub obstacle: Color,
+    pub spikes: Color,
+    // the wide pipes every few points
+    pub boss: Color,
+    // the best run raced as a ghost
+    pub ghost: Color,
+    pub coin: Color,
+    // shield power-up, and the player while shielded
+    pub shield: Color,
+    pub slowmo: Color,
+    // gravity flip power-up, and its time left in the HUD
+    pub flip: Color,
+    // magnet power-up, and its time left in the HUD
+    pub magnet: Color,
+    // lives left in the HUD
+    pub heart: Color,
+    // HUD text
+    pub text: Color,
+    // the strip of ground along the bottom
+    pub ground: Color,
+    // sky colors the day/night cycle blends between, looping back to the first
+    pub sky: [Color; 4],
+    // background layer colors, far first
+    pub scenery: [Color; 2],
+}
+
+// the original colors
+const CLASSIC: Theme = Theme {
+    player: YELLOW,
+    player2: ORANGE,
+    obstacle: RED,
+    spikes: LIGHT_GRAY,
+    boss: MAGENTA,
+    ghost: DARK_GRAY,
+    coin: GOLD,
+    shield: CYAN,
+    slowmo: GREEN,
+    flip: PINK,
+    magnet: ORANGE,
+    heart: RED,
+    text: WHITE,
+    ground: SADDLE_BROWN,
+    // evening, dusk, night, dawn
+    sky: [NAVY, (0, 0, 80), BLACK, (90, 40, 100)],
+    scenery: [GRAY, LIGHT_BLUE],
+};
+
+// shades of gray only
+const MONO: Theme = Theme {
+    player: WHITE,
+    player2: LIGHT_GRAY,
+    obstacle: GRAY,
+    spikes: LIGHT_GRAY,
+    boss: WHITE,
+    ghost: DIM_GRAY,
+    coin: WHITE,
+    shield: LIGHT_GRAY,
+    slowmo: LIGHT_GRAY,
+    flip: LIGHT_GRAY,
+    magnet: LIGHT_GRAY,
+    heart: WHITE,
+    text: WHITE,
+    ground: GRAY,
+    sky: [(30, 30, 30), (15, 15, 15), BLACK, (15, 15, 15)],
+    scenery: [DIM_GRAY, GRAY],
+};
+
+// bright colors on a dark purple sky
+const NEON: Theme = Theme {
+    player: MAGENTA,
+    player2: ORANGE,
+    obstacle: LIME,
+    spikes: HOT_PINK,
+    boss: ORANGE_RED,
+    ghost: PURPLE,
+    coin: YELLOW,
+    shield: CYAN,
+    slowmo: SPRING_GREEN,
+    flip: GOLD,
+    magnet: ORANGE_RED,
+    heart: HOT_PINK,
+    text: CYAN,
+    ground: MEDIUM_PURPLE,
+    sky: [(40, 0, 60), (20, 0, 40), BLACK, (60, 0, 50)],
+    scenery: [MEDIUM_PURPLE, DEEP_PINK],
+};
+
+// the Okabe-Ito colors, told apart with any kind of color blindness
+// the player is the brightest thing on screen and the obstacles a blue
+// far darker than it, so the two never rely on red against green,
+// hazards keep glyphs of their own too
+const COLORBLIND: Theme = Theme {
+    player: (240, 228, 66),
+    player2: (204, 121, 167),
+    obstacle: (0, 114, 178),
+    spikes: WHITE,
+    boss: (86, 180, 233),
+    ghost: DIM_GRAY,
+    coin: (230, 159, 0),
+    shield: (0, 158, 115),
+    slowmo: (86, 180, 233),
+    flip: (204, 121, 167),
+    magnet: (213, 94, 0),
+    heart: (213, 94, 0),
+    text: WHITE,
+    ground: GRAY,
+    // grays only, so no hue in the sky can blend into the colors above
+    sky: [(35, 35, 35), (20, 20, 20), BLACK, (20, 20, 20)],
+    scenery: [DIM_GRAY, GRAY],
+};
+
+// for low vision, the brightest colors on a black sky
+// used instead of the chosen theme while accessibility is on
+pub const HIGH_CONTRAST: Theme = Theme {
+    player: YELLOW,
+    player2: CYAN,
+    obstacle: WHITE,
+    spikes: RED,
+    boss: MAGENTA,
+    ghost: GRAY,
+    coin: YELLOW,
+    shield: CYAN,
+    slowmo: LIME,
+    flip: MAGENTA,
+    magnet: ORANGE,
+    heart: RED,
+    text: WHITE,
+    ground: WHITE,
+    sky: [BLACK; 4],
+    scenery: [DIM_GRAY, DIM_GRAY],
+};
+
+// the themes to pick from on the settings screen
+// saved by name, "Classic", "Mono", "Neon" or "Colorblind"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ThemePreset {
+    Classic,
+    Mono,
+    Neon,
+    Colorblind,
+}
+
+impl ThemePreset {
+    pub fn theme(self) -> &'static Theme {
+        match self {
+            ThemePreset::Classic => &CLASSIC,
+            ThemePreset::Mono => &MONO,
+            ThemePreset::Neon => &NEON,
+            ThemePreset::Colorblind => &COLORBLIND,
+        }
+    }
+
+    // the next theme, wrapping around to Classic
+    pub fn next(self) -> Self {
+        match self {
+            ThemePreset::Classic => ThemePreset::Mono,
+            ThemePreset::Mono => ThemePreset::Neon,
+            ThemePreset::Neon => ThemePreset::Colorblind,
+            ThemePreset::Colorblind => ThemePreset::Classic,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// anything in the world that changes over time on its own, dt in seconds
+// whatever only scrolls past keeps the default, which does nothing
+pub trait Update {
+    fn update(&mut self, _dt: f32) {}
+}
+
+// move a whole kind of entity on by dt
+pub fn update_all<T: Update>(entities: &mut [T], dt: f32) {
+    for entity in entities {
+        entity.update(dt);
+    }
+}
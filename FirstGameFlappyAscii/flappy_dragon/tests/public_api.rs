@@ -0,0 +1,79 @@
+// the game's pieces put together from outside the crate,
+// only with what the library makes public
+use bracket_lib::prelude::RandomNumberGenerator;
+use flappy_dragon::{Difficulty, GameConfig, Obstacle, ObstacleKind, PhysicsConfig, Player, Screen};
+
+// one physics frame of the config, in seconds
+fn step(config: &GameConfig) -> f32 {
+    config.frame_duration / 1000.0
+}
+
+#[test]
+fn a_flap_lifts_the_player_and_gravity_pulls_it_back_down() {
+    let config = GameConfig::default();
+    let mut player = Player::new(5, 25, &config.physics);
+    assert!(player.flap(&config.physics));
+    player.gravity_and_move(step(&config), &config.physics, 0.0);
+    assert!(player.y < 25, "flapped to {}", player.y);
+    for _ in 0..40 {
+        player.gravity_and_move(step(&config), &config.physics, 0.0);
+    }
+    assert!(player.y > 25, "fell to {}", player.y);
+    // and moved a column on every frame
+    assert_eq!(player.x, 5 + 41);
+}
+
+#[test]
+fn the_flaps_run_out_until_the_player_falls_again() {
+    let physics = PhysicsConfig {
+        coyote_frames: 0,
+        ..PhysicsConfig::default()
+    };
+    let mut player = Player::new(5, 25, &physics);
+    for _ in 0..physics.max_flaps {
+        assert!(player.flap(&physics));
+    }
+    assert!(!player.flap(&physics));
+}
+
+// the first pipe of a run, whatever the seed: flying through the middle
+// of its gap is safe, flying just past the end of the gap is not
+#[test]
+fn a_pipe_lets_the_player_through_its_gap_only() {
+    let config = GameConfig::default();
+    let height = config.screen.height;
+    let mut pipes = 0;
+    for seed in 0..50 {
+        let mut random = RandomNumberGenerator::seeded(seed);
+        let pipe = Obstacle::new(20, 0, config.base_gap, height, &mut random);
+        if pipe.kind != ObstacleKind::Pipe {
+            continue;
+        }
+        let through = Player::new(20, pipe.gap_y, &config.physics);
+        let wall_y = if pipe.gap_y < height / 2 {
+            pipe.gap_y + pipe.size / 2 + 1
+        } else {
+            pipe.gap_y - pipe.size / 2 - 1
+        };
+        let over = Player::new(20, wall_y, &config.physics);
+        assert!(!pipe.hit_obstacle(&through, 19), "seed {}", seed);
+        assert!(pipe.hit_obstacle(&over, 19), "seed {}", seed);
+        pipes += 1;
+    }
+    assert!(pipes > 0, "no seed started with a pipe");
+}
+
+#[test]
+fn harder_difficulties_scroll_faster_with_smaller_gaps() {
+    let configs: Vec<_> = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+        .iter()
+        .map(|&difficulty| GameConfig::new(Screen::default(), difficulty))
+        .collect();
+    for pair in configs.windows(2) {
+        assert!(pair[1].base_gap < pair[0].base_gap);
+        assert!(pair[1].frame_duration < pair[0].frame_duration);
+    }
+    // and every run speeds up as the score goes up
+    let normal = &configs[1];
+    assert!(normal.frame_duration_for_score(100) < normal.frame_duration_for_score(0));
+}
@@ -0,0 +1,42 @@
+// simulate() through the public API, the same as any other tool would use it
+use flappy_dragon::{simulate, GameConfig, ScoringMode};
+
+// a tap on every eighth frame, never passing a gap but flying for a while
+fn every_eighth_frame() -> Vec<bool> {
+    (0..3000).map(|frame| frame % 8 == 0).collect()
+}
+
+// counted by distance, so the score shows exactly how long the run lasted
+fn distance_config() -> GameConfig {
+    GameConfig {
+        scoring: ScoringMode::Distance,
+        ..GameConfig::default()
+    }
+}
+
+#[test]
+fn known_inputs_give_an_exact_score() {
+    let inputs = every_eighth_frame();
+    assert_eq!(simulate(7, &inputs, &distance_config()), 62);
+    // and again, nothing left over from the first run
+    assert_eq!(simulate(7, &inputs, &distance_config()), 62);
+}
+
+// falls into the ground once for every life of the Normal difficulty
+#[test]
+fn no_flaps_use_up_every_life() {
+    assert_eq!(simulate(7, &[false; 3000], &distance_config()), 66);
+}
+
+#[test]
+fn changing_the_config_changes_the_score() {
+    let inputs = every_eighth_frame();
+    let mut config = distance_config();
+    config.physics.gravity = 0.2;
+    assert_eq!(simulate(7, &inputs, &config), 65);
+}
+
+#[test]
+fn running_out_of_inputs_ends_the_run() {
+    assert_eq!(simulate(7, &[], &distance_config()), 0);
+}